@@ -1,20 +1,58 @@
 pub mod color;
+pub mod metric;
 pub mod r#move;
 pub mod simple_move;
+pub mod slice_move;
 pub mod fast_move;
+pub mod mask;
 pub mod move_sequence;
+pub mod notation;
 pub mod piece;
 
+#[cfg(test)]
+mod tests;
+
 use std::fmt::Display;
 
-use color::Color::*;
+use color::Color::{self, *};
+use rand::{Rng, seq::SliceRandom};
 
+use crate::cube::mask::{Care, Mask};
 use crate::cube::r#move::Move;
 use crate::cube::move_sequence::Sequence;
 use crate::cube::piece::Corner;
 use crate::cube::piece::Edge;
+use crate::cube::simple_move::Side::{self, *};
+
+/// The sides each corner slot touches, in the order of its stickers.
+pub const CORNER_SIDES: [[Side; 3]; 8] = [
+    [UP, RIGHT, FRONT],
+    [UP, BACK, RIGHT],
+    [UP, LEFT, BACK],
+    [UP, FRONT, LEFT],
+    [DOWN, FRONT, RIGHT],
+    [DOWN, RIGHT, BACK],
+    [DOWN, BACK, LEFT],
+    [DOWN, LEFT, FRONT],
+];
 
-#[derive(Debug, Clone, PartialEq, Eq)]
+/// The sides each edge slot touches, in the order of its stickers.
+pub const EDGE_SIDES: [[Side; 2]; 12] = [
+    [UP, FRONT],
+    [UP, RIGHT],
+    [UP, BACK],
+    [UP, LEFT],
+    [FRONT, RIGHT],
+    [BACK, RIGHT],
+    [BACK, LEFT],
+    [FRONT, LEFT],
+    [DOWN, FRONT],
+    [DOWN, RIGHT],
+    [DOWN, BACK],
+    [DOWN, LEFT],
+];
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
 pub struct Cube {
     corner_permutation: [u8; 8],
     corner_orientation: [u8; 8],
@@ -60,12 +98,44 @@ impl Cube {
         }
     }
 
+    /// A random cube that `mask` matches, every one as likely: the pieces
+    /// it cares about fully are solved, the ones it cares about the
+    /// orientation of are oriented anywhere the others can be, and the rest
+    /// are anywhere, turned any way.
+    pub fn random<R: Rng + ?Sized>(rng: &mut R, mask: &Mask) -> Self {
+        let mut cube = Cube::create_solved();
+        let corners = scatter(
+            rng,
+            &mut cube.corner_permutation,
+            &mut cube.corner_orientation,
+            |corner| mask.corner(corner),
+            3,
+        );
+        let edges = scatter(
+            rng,
+            &mut cube.edge_permutation,
+            &mut cube.edge_orientation,
+            |edge| mask.edge(edge),
+            2,
+        );
+        // Swapping two of the pieces that moved fixes the parity, and leaves
+        // every cube with either parity as likely.
+        if cube.corner_parity() != cube.edge_parity() {
+            if let [first, second, ..] = edges[..] {
+                cube.edge_permutation.swap(first, second);
+                cube.edge_orientation.swap(first, second);
+            } else if let [first, second, ..] = corners[..] {
+                cube.corner_permutation.swap(first, second);
+                cube.corner_orientation.swap(first, second);
+            }
+        }
+        cube
+    }
+
     pub fn apply_move<T: Move>(&self, r#move: &T) -> Self {
         r#move.apply(self)
     }
 
-    
-
     pub fn apply<T: Move>(&self, scramble: &Sequence<T>) -> Self {
         let mut new: Cube = self.clone();
         for r#move in scramble.into_iter() {
@@ -73,98 +143,227 @@ impl Cube {
         }
         new
     }
-}
 
-impl Display for Cube {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    /// Moves the pieces the way they moved from the solved cube to `other`,
+    /// as if the moves that scrambled `other` were applied to this cube.
+    pub fn compose(&self, other: &Cube) -> Self {
+        Cube {
+            corner_permutation: std::array::from_fn(|index| {
+                self.corner_permutation[other.corner_permutation[index] as usize]
+            }),
+            corner_orientation: std::array::from_fn(|index| {
+                (self.corner_orientation[other.corner_permutation[index] as usize]
+                    + other.corner_orientation[index])
+                    % 3
+            }),
+            edge_permutation: std::array::from_fn(|index| {
+                self.edge_permutation[other.edge_permutation[index] as usize]
+            }),
+            edge_orientation: std::array::from_fn(|index| {
+                self.edge_orientation[other.edge_permutation[index] as usize]
+                    ^ other.edge_orientation[index]
+            }),
+        }
+    }
+
+    /// The cube that `compose` undoes this one with, as if the moves that
+    /// scrambled this cube were undone.
+    pub fn inverse(&self) -> Self {
+        let mut inverse = self.clone();
+        for (slot, &piece) in self.corner_permutation.iter().enumerate() {
+            inverse.corner_permutation[piece as usize] = slot as u8;
+            inverse.corner_orientation[piece as usize] = (3 - self.corner_orientation[slot]) % 3;
+        }
+        for (slot, &piece) in self.edge_permutation.iter().enumerate() {
+            inverse.edge_permutation[piece as usize] = slot as u8;
+            inverse.edge_orientation[piece as usize] = self.edge_orientation[slot];
+        }
+        inverse
+    }
+
+    /// This cube with only the pieces in the slots `corners` and `edges`
+    /// moved, and every other one solved. The slots must hold each other's
+    /// pieces, like the slots of a cycle.
+    pub fn only(&self, corners: &[usize], edges: &[usize]) -> Self {
+        let mut only = Cube::create_solved();
+        for &slot in corners {
+            only.corner_permutation[slot] = self.corner_permutation[slot];
+            only.corner_orientation[slot] = self.corner_orientation[slot];
+        }
+        for &slot in edges {
+            only.edge_permutation[slot] = self.edge_permutation[slot];
+            only.edge_orientation[slot] = self.edge_orientation[slot];
+        }
+        only
+    }
+
+    /// The stickers of every face, indexed by `Side`, each row by row as laid
+    /// out by `Display`: the sides seen from the front with `U` above them,
+    /// `U` with `B` at the top and `D` with `F` at the top.
+    pub fn faces(&self) -> [[Color; 9]; 6] {
         let corners: [Corner; 8] = Cube::create_solved_corners();
-        let corners: [Corner; 8] = std::array::from_fn(|index| {
+        let c: [Corner; 8] = std::array::from_fn(|index| {
             corners[self.corner_permutation[index] as usize].rotate(self.corner_orientation[index])
         });
 
         let edges: [Edge; 12] = Cube::create_solved_edges();
-        let edges: [Edge; 12] = std::array::from_fn(|index| {
+        let e: [Edge; 12] = std::array::from_fn(|index| {
             edges[self.edge_permutation[index] as usize].flip(self.edge_orientation[index])
         });
 
-        write!(
-            f,
-            "   {}{}{}      \n   {}{}{}      \n   {}{}{}      \n{}{}{}{}{}{}{}{}{}{}{}{}\n{}{}{}{}{}{}{}{}{}{}{}{}\n{}{}{}{}{}{}{}{}{}{}{}{}\n   {}{}{}      \n   {}{}{}      \n   {}{}{}      \n",
-            corners[2].0,
-            edges[2].0,
-            corners[1].0,
-            //
-            edges[3].0,
-            WHITE,
-            edges[1].0,
-            //
-            corners[3].0,
-            edges[0].0,
-            corners[0].0,
-            //
-            //
-            corners[2].1,
-            edges[3].1,
-            corners[3].2,
-            //
-            corners[3].1,
-            edges[0].1,
-            corners[0].2,
-            //
-            corners[0].1,
-            edges[1].1,
-            corners[1].2,
-            //
-            corners[1].1,
-            edges[2].1,
-            corners[2].2,
-            //
-            //
-            edges[6].1,
-            ORANGE,
-            edges[7].1,
-            //
-            edges[7].0,
-            GREEN,
-            edges[4].0,
-            //
-            edges[4].1,
-            RED,
-            edges[5].1,
-            //
-            edges[5].0,
-            BLUE,
-            edges[6].0,
-            //
-            //
-            corners[6].2,
-            edges[11].1,
-            corners[7].1,
-            //
-            corners[7].2,
-            edges[8].1,
-            corners[4].1,
-            //
-            corners[4].2,
-            edges[9].1,
-            corners[5].1,
-            //
-            corners[5].2,
-            edges[10].1,
-            corners[6].1,
-            //
-            //
-            corners[7].0,
-            edges[8].0,
-            corners[4].0,
-            //
-            edges[11].0,
-            YELLOW,
-            edges[9].0,
-            //
-            corners[6].0,
-            edges[10].0,
-            corners[5].0,
-        )
+        [
+            [c[2].0, e[2].0, c[1].0, e[3].0, WHITE, e[1].0, c[3].0, e[0].0, c[0].0],
+            [c[7].0, e[8].0, c[4].0, e[11].0, YELLOW, e[9].0, c[6].0, e[10].0, c[5].0],
+            [c[3].1, e[0].1, c[0].2, e[7].0, GREEN, e[4].0, c[7].2, e[8].1, c[4].1],
+            [c[1].1, e[2].1, c[2].2, e[5].0, BLUE, e[6].0, c[5].2, e[10].1, c[6].1],
+            [c[0].1, e[1].1, c[1].2, e[4].1, RED, e[5].1, c[4].2, e[9].1, c[5].1],
+            [c[2].1, e[3].1, c[3].2, e[6].1, ORANGE, e[7].1, c[6].2, e[11].1, c[7].1],
+        ]
+    }
+
+    pub fn corner_permutation(&self) -> &[u8; 8] {
+        &self.corner_permutation
+    }
+
+    pub fn corner_orientation(&self) -> &[u8; 8] {
+        &self.corner_orientation
+    }
+
+    pub fn edge_permutation(&self) -> &[u8; 12] {
+        &self.edge_permutation
+    }
+
+    pub fn edge_orientation(&self) -> &[u8; 12] {
+        &self.edge_orientation
     }
+
+    /// The twist of each corner relative to the axis of `side`, 0 when its
+    /// sticker of that axis is on one of the axis' sides. Along `UP` this is
+    /// `corner_orientation`.
+    pub fn corner_orientation_along(&self, side: Side) -> [u8; 8] {
+        let reference = |sides: &[Side; 3]| {
+            sides
+                .iter()
+                .position(|corner_side| corner_side.axis() == side.axis())
+                .unwrap_or(0) as u8
+        };
+        std::array::from_fn(|slot| {
+            let piece = self.corner_permutation[slot] as usize;
+            (self.corner_orientation[slot] + reference(&CORNER_SIDES[piece]) + 3
+                - reference(&CORNER_SIDES[slot]))
+                % 3
+        })
+    }
+
+    /// The flip of each edge relative to the axis of `side`, 0 when it can be
+    /// solved without quarter turns of that axis. Along `FRONT` this is
+    /// `edge_orientation`.
+    pub fn edge_orientation_along(&self, side: Side) -> [u8; 12] {
+        // Each edge is judged by its sticker on `U` or `D`, or on `F` or `B`
+        // along the `U` and `D` axis, and otherwise by its sticker of the
+        // axis itself.
+        let first = if side.axis() == UP.axis() {
+            FRONT.axis()
+        } else {
+            UP.axis()
+        };
+        let reference = |sides: &[Side; 2]| {
+            (sides[1].axis() == first || sides[0].axis() != first && sides[1].axis() == side.axis())
+                as u8
+        };
+        std::array::from_fn(|slot| {
+            let piece = self.edge_permutation[slot] as usize;
+            self.edge_orientation[slot]
+                ^ reference(&EDGE_SIDES[piece])
+                ^ reference(&EDGE_SIDES[slot])
+        })
+    }
+
+    pub fn corner_twist(&self) -> u8 {
+        self.corner_orientation.iter().sum::<u8>() % 3
+    }
+
+    pub fn edge_flip(&self) -> u8 {
+        self.edge_orientation.iter().sum::<u8>() % 2
+    }
+
+    pub fn corner_parity(&self) -> bool {
+        permutation_parity(&self.corner_permutation)
+    }
+
+    pub fn edge_parity(&self) -> bool {
+        permutation_parity(&self.edge_permutation)
+    }
+
+    /// A state is reachable by face turns exactly when the corner twist and
+    /// edge flip cancel out and both permutations share the same parity.
+    pub fn is_solvable(&self) -> bool {
+        self.corner_twist() == 0
+            && self.edge_flip() == 0
+            && self.corner_parity() == self.edge_parity()
+    }
+}
+
+/// Shuffles the pieces that `care` doesn't keep in place between their slots
+/// and turns the ones it doesn't keep oriented at random, with the last one
+/// turned so the total twist stays solvable. Returns the slots shuffled.
+fn scatter<const N: usize, R: Rng + ?Sized>(
+    rng: &mut R,
+    permutation: &mut [u8; N],
+    orientation: &mut [u8; N],
+    care: impl Fn(usize) -> Care,
+    twists: u8,
+) -> Vec<usize> {
+    let slots: Vec<usize> = (0..N).filter(|&piece| care(piece) != Care::Full).collect();
+    let mut pieces = slots.clone();
+    pieces.shuffle(rng);
+    for (&slot, &piece) in slots.iter().zip(&pieces) {
+        permutation[slot] = piece as u8;
+    }
+    let turned: Vec<usize> = (0..N)
+        .filter(|&slot| care(permutation[slot] as usize) == Care::Ignore)
+        .collect();
+    for &slot in &turned {
+        orientation[slot] = rng.random_range(0..twists);
+    }
+    if let Some(&last) = turned.last() {
+        let twist = orientation.iter().sum::<u8>() % twists;
+        orientation[last] = (orientation[last] + twists - twist) % twists;
+    }
+    slots
+}
+
+/// Parity of a permutation, `true` when odd, counted through its inversions.
+fn permutation_parity(permutation: &[u8]) -> bool {
+    let mut inversions = 0;
+    for (i, a) in permutation.iter().enumerate() {
+        inversions += permutation[i + 1..].iter().filter(|b| *b < a).count();
+    }
+    inversions % 2 == 1
+}
+
+impl Display for Cube {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let [up, down, front, back, right, left] = self.faces();
+        for row in 0..3 {
+            writeln!(f, "   {}{}{}      ", up[row * 3], up[row * 3 + 1], up[row * 3 + 2])?;
+        }
+        for row in 0..3 {
+            for face in [&left, &front, &right, &back] {
+                write!(f, "{}{}{}", face[row * 3], face[row * 3 + 1], face[row * 3 + 2])?;
+            }
+            writeln!(f)?;
+        }
+        for row in 0..3 {
+            writeln!(f, "   {}{}{}      ", down[row * 3], down[row * 3 + 1], down[row * 3 + 2])?;
+        }
+        Ok(())
+    }
+}
+
+/// The solved cube turned by `scramble`, written in any notation
+/// `notation::expand` reads.
+#[cfg(test)]
+pub(crate) fn scrambled(scramble: &str) -> Cube {
+    Cube::create_solved().apply(&notation::expand::<simple_move::SimpleMove>(scramble).unwrap())
 }
@@ -1,5 +1,8 @@
 use std::fmt::Display;
 
+use crate::cube::simple_move::Side;
+
+#[allow(clippy::upper_case_acronyms)]
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum Color {
     WHITE,
@@ -10,6 +13,20 @@ pub enum Color {
     YELLOW,
 }
 
+impl Color {
+    /// The side with this color in the solved cube.
+    pub fn side(self) -> Side {
+        match self {
+            Color::WHITE => Side::UP,
+            Color::GREEN => Side::FRONT,
+            Color::RED => Side::RIGHT,
+            Color::BLUE => Side::BACK,
+            Color::ORANGE => Side::LEFT,
+            Color::YELLOW => Side::DOWN,
+        }
+    }
+}
+
 impl Display for Color {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         let char = match self {
@@ -1,4 +1,6 @@
-use std::{fmt::Display, str::FromStr, sync::LazyLock};
+use std::{fmt::Display, str::FromStr};
+
+use rand::distr::{Distribution, StandardUniform};
 
 use crate::cube::{
     Cube,
@@ -6,13 +8,12 @@ use crate::cube::{
     simple_move::{Side, Turn},
 };
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
 pub struct FastMove {
     pub side: Side,
     pub turns: Turn,
 }
 
-
 impl FastMove {
     fn rotate(&self, cube: &Cube) -> Cube {
         let corner_permutation = match self.turns {
@@ -63,11 +64,32 @@ impl Move for FastMove {
         self.rotate(cube)
     }
 
+    fn side(&self) -> Side {
+        self.side
+    }
+
+    fn turns(&self) -> Turn {
+        self.turns
+    }
+
+    fn invert(&self) -> Self {
+        FastMove {
+            side: self.side,
+            turns: self.turns.invert(),
+        }
+    }
+
     fn all_moves() -> [Self; 18] {
         ALL_MOVES
     }
 }
 
+impl Distribution<FastMove> for StandardUniform {
+    fn sample<R: rand::Rng + ?Sized>(&self, rng: &mut R) -> FastMove {
+        ALL_MOVES[rng.random_range(0..ALL_MOVES.len())].clone()
+    }
+}
+
 impl FromStr for FastMove {
     type Err = ();
 
@@ -96,7 +118,7 @@ impl Display for FastMove {
     }
 }
 
-const ALL_MOVES_STR: [&'static str; 18] = [
+const ALL_MOVES_STR: [&str; 18] = [
     "U", "U'", "U2", "D", "D'", "D2", "F", "F'", "F2", "B", "B'", "B2", "R", "R'", "R2", "L", "L'",
     "L2",
 ];
@@ -0,0 +1,192 @@
+use crate::cube::{CORNER_SIDES, Cube, EDGE_SIDES, simple_move::Side};
+
+/// How much of a piece has to be solved.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Care {
+    Ignore,
+    /// The piece may be anywhere, as long as it's oriented.
+    Orientation,
+    Full,
+}
+
+/// A partial goal state, saying which pieces have to be solved and how.
+///
+/// Pieces are indexed like the permutation arrays of `Cube`, so corner 0 is
+/// the piece that belongs in the first corner slot. With white on top, the
+/// white cross is edges 0 to 3.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Mask {
+    corners: [Care; 8],
+    edges: [Care; 12],
+}
+
+impl Mask {
+    pub fn all() -> Self {
+        Mask {
+            corners: [Care::Full; 8],
+            edges: [Care::Full; 12],
+        }
+    }
+
+    pub fn none() -> Self {
+        Mask {
+            corners: [Care::Ignore; 8],
+            edges: [Care::Ignore; 12],
+        }
+    }
+
+    pub fn cross() -> Self {
+        Mask::cross_on(Side::UP)
+    }
+
+    pub fn edge_orientation() -> Self {
+        Mask::none().with_edges(&(0..12).collect::<Vec<_>>(), Care::Orientation)
+    }
+
+    pub fn first_layer() -> Self {
+        Mask::first_layer_on(Side::UP)
+    }
+
+    pub fn first_two_layers() -> Self {
+        Mask::first_two_layers_on(Side::UP)
+    }
+
+    /// The four edges of `side`.
+    pub fn cross_on(side: Side) -> Self {
+        Mask::none().with_edges(&edges_touching(|sides| sides.contains(&side)), Care::Full)
+    }
+
+    pub fn first_layer_on(side: Side) -> Self {
+        Mask::cross_on(side).with_corners(&corners_touching(side), Care::Full)
+    }
+
+    pub fn first_two_layers_on(side: Side) -> Self {
+        let middle =
+            edges_touching(|sides| !sides.contains(&side) && !sides.contains(&side.opposite()));
+        Mask::first_layer_on(side).with_edges(&middle, Care::Full)
+    }
+
+    /// The F2L pair of the corner slot `corner`, on the layer of `side`: the
+    /// corner and the middle layer edge next to it.
+    pub fn pair(side: Side, corner: usize) -> Self {
+        let edge = edges_touching(|sides| {
+            !sides.contains(&side)
+                && sides
+                    .iter()
+                    .all(|edge_side| CORNER_SIDES[corner].contains(edge_side))
+        });
+        Mask::none()
+            .with_corners(&[corner], Care::Full)
+            .with_edges(&edge, Care::Full)
+    }
+
+    /// Every edge oriented, with `DF` and `DB` solved.
+    pub fn eo_line() -> Self {
+        Mask::eo_line_on(Side::DOWN)
+    }
+
+    /// Every edge oriented, with the edges of `side` next to `F` and `B`
+    /// solved.
+    pub fn eo_line_on(side: Side) -> Self {
+        let line = edges_touching(|sides| {
+            sides.contains(&side) && (sides.contains(&Side::FRONT) || sides.contains(&Side::BACK))
+        });
+        Mask::edge_orientation().with_edges(&line, Care::Full)
+    }
+
+    /// Roux's first block, the 1x2x3 block on the bottom of `L`.
+    pub fn first_block() -> Self {
+        Mask::block(Side::LEFT, Side::DOWN)
+    }
+
+    /// Roux's second block, across from the first one on `R`.
+    pub fn second_block() -> Self {
+        Mask::block(Side::RIGHT, Side::DOWN)
+    }
+
+    /// The 1x2x3 block on `side` that runs along `bottom`: the two corners
+    /// and the edge between them, and the two middle edges above them.
+    pub fn block(side: Side, bottom: Side) -> Self {
+        let edges =
+            edges_touching(|sides| sides.contains(&side) && !sides.contains(&bottom.opposite()));
+        let corners: Vec<usize> = corners_touching(side)
+            .into_iter()
+            .filter(|&corner| CORNER_SIDES[corner].contains(&bottom))
+            .collect();
+        Mask::none()
+            .with_corners(&corners, Care::Full)
+            .with_edges(&edges, Care::Full)
+    }
+
+    /// The corner slots on `side`, which are also the F2L slots of a cross on
+    /// that side.
+    pub fn slots(side: Side) -> Vec<usize> {
+        corners_touching(side)
+    }
+
+    pub fn with_corners(mut self, corners: &[usize], care: Care) -> Self {
+        for &corner in corners {
+            self.corners[corner] = care;
+        }
+        self
+    }
+
+    pub fn with_edges(mut self, edges: &[usize], care: Care) -> Self {
+        for &edge in edges {
+            self.edges[edge] = care;
+        }
+        self
+    }
+
+    /// Cares about everything either mask cares about.
+    pub fn union(&self, other: &Mask) -> Mask {
+        Mask {
+            corners: std::array::from_fn(|i| self.corners[i].max(other.corners[i])),
+            edges: std::array::from_fn(|i| self.edges[i].max(other.edges[i])),
+        }
+    }
+
+    pub fn corner(&self, corner: usize) -> Care {
+        self.corners[corner]
+    }
+
+    pub fn edge(&self, edge: usize) -> Care {
+        self.edges[edge]
+    }
+
+    pub fn is_full(&self) -> bool {
+        *self == Mask::all()
+    }
+
+    pub fn matches(&self, cube: &Cube) -> bool {
+        let corners = (0..8).all(|position| {
+            let piece = cube.corner_permutation[position] as usize;
+            match self.corners[piece] {
+                Care::Ignore => true,
+                Care::Orientation => cube.corner_orientation[position] == 0,
+                Care::Full => piece == position && cube.corner_orientation[position] == 0,
+            }
+        });
+        let edges = (0..12).all(|position| {
+            let piece = cube.edge_permutation[position] as usize;
+            match self.edges[piece] {
+                Care::Ignore => true,
+                Care::Orientation => cube.edge_orientation[position] == 0,
+                Care::Full => piece == position && cube.edge_orientation[position] == 0,
+            }
+        });
+        corners && edges
+    }
+}
+
+fn corners_touching(side: Side) -> Vec<usize> {
+    (0..8)
+        .filter(|&corner| CORNER_SIDES[corner].contains(&side))
+        .collect()
+}
+
+fn edges_touching(condition: impl Fn(&[Side; 2]) -> bool) -> Vec<usize> {
+    (0..12)
+        .filter(|&edge| condition(&EDGE_SIDES[edge]))
+        .collect()
+}
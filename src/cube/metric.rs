@@ -0,0 +1,96 @@
+use std::{fmt::Display, str::FromStr};
+
+use crate::cube::{notation, r#move::Move, simple_move::Turn};
+
+/// How moves are counted.
+///
+/// - `HTM`: every face or wide turn counts as one, a slice turn as the two
+///   face turns it's made of.
+/// - `QTM`: like `HTM`, with half turns counting twice.
+/// - `STM`: every face, wide or slice turn counts as one.
+/// - `ETM`: every move, including rotations, counts as one.
+///
+/// Rotations only count in `ETM`. Face turns alone count the same in `HTM`,
+/// `STM` and `ETM`, so the metrics only differ on a `Sequence` in quarter
+/// turns; `count` tells them apart on an algorithm as it's written.
+#[allow(clippy::upper_case_acronyms)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum Metric {
+    #[default]
+    HTM,
+    QTM,
+    STM,
+    ETM,
+}
+
+impl Metric {
+    pub fn cost<T: Move>(&self, r#move: &T) -> usize {
+        match (self, r#move.turns()) {
+            (Metric::QTM, Turn::TWO) => 2,
+            _ => 1,
+        }
+    }
+
+    /// The length of an algorithm written in full notation, with wide moves,
+    /// slice moves and rotations. Moves that aren't understood count as face
+    /// turns.
+    pub fn count(&self, algorithm: &str) -> usize {
+        notation::split(algorithm)
+            .iter()
+            .map(|r#move| {
+                let rotation = r#move.starts_with(['x', 'y', 'z']);
+                let slice = r#move.starts_with(['M', 'E', 'S']) && !r#move.contains('w');
+                let quarter_turns = if r#move.contains('2') { 2 } else { 1 };
+                match self {
+                    Metric::ETM => 1,
+                    _ if rotation => 0,
+                    Metric::HTM if slice => 2,
+                    Metric::QTM if slice => 2 * quarter_turns,
+                    Metric::QTM => quarter_turns,
+                    Metric::HTM | Metric::STM => 1,
+                }
+            })
+            .sum()
+    }
+}
+
+impl FromStr for Metric {
+    type Err = ();
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_ascii_uppercase().as_str() {
+            "HTM" => Ok(Metric::HTM),
+            "QTM" => Ok(Metric::QTM),
+            "STM" => Ok(Metric::STM),
+            "ETM" => Ok(Metric::ETM),
+            _ => Err(()),
+        }
+    }
+}
+
+impl Display for Metric {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let name = match self {
+            Metric::HTM => "HTM",
+            Metric::QTM => "QTM",
+            Metric::STM => "STM",
+            Metric::ETM => "ETM",
+        };
+        write!(f, "{}", name)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn counts_written_moves() {
+        let algorithm = "x (R U2 r' M2) y' Rw E'";
+        assert_eq!(Metric::HTM.count(algorithm), 8);
+        assert_eq!(Metric::QTM.count(algorithm), 11);
+        assert_eq!(Metric::STM.count(algorithm), 6);
+        assert_eq!(Metric::ETM.count(algorithm), 8);
+        assert_eq!(Metric::STM.count(""), 0);
+    }
+}
@@ -1,9 +1,27 @@
 use std::fmt::Debug;
 
-use crate::cube::Cube;
+use crate::cube::{
+    Cube,
+    simple_move::{Side, Turn},
+};
 
 pub trait Move: Debug + Clone {
     fn apply(&self, cube: &Cube) -> Cube;
 
+    fn side(&self) -> Side;
+
+    fn turns(&self) -> Turn;
+
+    fn invert(&self) -> Self;
+
     fn all_moves() -> [Self; 18];
+
+    fn from_parts(side: Side, turns: Turn) -> Self {
+        let turn_index = match turns {
+            Turn::RIGHT => 0,
+            Turn::LEFT => 1,
+            Turn::TWO => 2,
+        };
+        Self::all_moves()[side as usize * 3 + turn_index].clone()
+    }
 }
@@ -2,28 +2,88 @@ use std::{fmt::Display, str::FromStr};
 
 use rand::distr::{Distribution, StandardUniform};
 
-use crate::cube::r#move::Move;
+use crate::cube::{metric::Metric, r#move::Move, simple_move::Turn};
 
-#[derive(Debug)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
 pub struct Sequence<T: Move> {
     moves: Vec<T>,
 }
 impl<T: Move> Sequence<T> {
     pub fn new() -> Self {
-        Self {
-            moves: Vec::new(),
-        }
+        Self { moves: Vec::new() }
     }
     pub fn apply(&self, r#move: &T) -> Self {
         let mut new_moves = self.moves.clone();
         new_moves.push(r#move.clone());
         Self { moves: new_moves }
     }
-    // pub fn invert(&self) -> Self {
-    //     Sequence {
-    //         moves: self.into_iter().rev().map(|m| m.invert()).collect(),
-    //     }
-    // }
+    pub fn len(&self) -> usize {
+        self.moves.len()
+    }
+    pub fn length(&self, metric: Metric) -> usize {
+        self.moves.iter().map(|m| metric.cost(m)).sum()
+    }
+    pub fn is_empty(&self) -> bool {
+        self.moves.is_empty()
+    }
+    pub fn moves(&self) -> &[T] {
+        &self.moves
+    }
+    pub fn concat(&self, other: &Self) -> Self {
+        Sequence {
+            moves: self.moves.iter().chain(&other.moves).cloned().collect(),
+        }
+    }
+    /// Merges turns of the same side that follow each other, also across a
+    /// turn of the opposite side, and drops the ones that cancel out.
+    pub fn simplify(&self) -> Self {
+        let mut moves: Vec<T> = Vec::new();
+        for r#move in &self.moves {
+            let len = moves.len();
+            let target = if len >= 1 && moves[len - 1].side() == r#move.side() {
+                Some(len - 1)
+            } else if len >= 2
+                && moves[len - 1].side().axis() == r#move.side().axis()
+                && moves[len - 2].side() == r#move.side()
+            {
+                Some(len - 2)
+            } else {
+                None
+            };
+            let Some(target) = target else {
+                moves.push(r#move.clone());
+                continue;
+            };
+            let quarter_turns =
+                moves[target].turns().quarter_turns() + r#move.turns().quarter_turns();
+            match Turn::from_quarter_turns(quarter_turns) {
+                Some(turns) => moves[target] = T::from_parts(r#move.side(), turns),
+                None => {
+                    moves.remove(target);
+                }
+            }
+        }
+        Sequence { moves }
+    }
+    pub fn invert(&self) -> Self {
+        Sequence {
+            moves: self.moves.iter().rev().map(|m| m.invert()).collect(),
+        }
+    }
+}
+
+impl<T: Move> FromIterator<T> for Sequence<T> {
+    fn from_iter<I: IntoIterator<Item = T>>(iter: I) -> Self {
+        Sequence {
+            moves: iter.into_iter().collect(),
+        }
+    }
+}
+
+impl<T: Move> Default for Sequence<T> {
+    fn default() -> Self {
+        Self::new()
+    }
 }
 
 impl<T: Move + FromStr> FromStr for Sequence<T> {
@@ -32,7 +92,7 @@ impl<T: Move + FromStr> FromStr for Sequence<T> {
     fn from_str(input: &str) -> Result<Self, Self::Err> {
         Ok(Sequence {
             moves: input
-                .split(' ')
+                .split_whitespace()
                 .map(|s| s.parse::<T>())
                 .collect::<Result<Vec<T>, Self::Err>>()?,
         })
@@ -0,0 +1,206 @@
+use crate::cube::{
+    r#move::Move,
+    move_sequence::Sequence,
+    simple_move::{Side, Turn},
+};
+
+/// Rewrites an algorithm in full notation, with wide moves (`r`, `Rw`), slice
+/// moves (`M`, `E`, `S`), rotations (`x`, `y`, `z`) and brackets, as face
+/// turns only.
+///
+/// `Cube` has no centers and tracks pieces relative to them, so a wide or
+/// slice move is the face turn on the opposite side of the same layers
+/// followed by a rotation, and rotations only change which physical side later
+/// moves turn. The pieces end up exactly where they would on a real cube,
+/// seen from its centers.
+#[allow(clippy::result_unit_err)]
+pub fn expand<T: Move>(algorithm: &str) -> Result<Sequence<T>, ()> {
+    let mut frame = HOME;
+    let moves: Vec<T> = expand_from(algorithm, &mut frame)?;
+    Ok(moves.into_iter().collect())
+}
+
+/// The frame before any rotation, with every side in its own place.
+pub(crate) const HOME: [Side; 6] = [
+    Side::UP,
+    Side::DOWN,
+    Side::FRONT,
+    Side::BACK,
+    Side::RIGHT,
+    Side::LEFT,
+];
+
+/// Like `expand`, starting from `frame`, where `frame[side]` is the physical
+/// side currently in the place of `side`, and leaving it as the algorithm
+/// turns it.
+pub(crate) fn expand_from<T: Move>(algorithm: &str, frame: &mut [Side; 6]) -> Result<Vec<T>, ()> {
+    let mut moves: Vec<T> = Vec::new();
+
+    let mut chars = algorithm
+        .chars()
+        .filter(|c| !c.is_whitespace() && !"()[]".contains(*c))
+        .peekable();
+    while let Some(letter) = chars.next() {
+        let wide = letter.is_ascii_lowercase() && !"xyz".contains(letter)
+            || chars.next_if_eq(&'w').is_some();
+        let turns = match (
+            chars.next_if(|c| c.is_ascii_digit()),
+            chars.next_if_eq(&'\''),
+        ) {
+            (Some('2'), _) => Turn::TWO,
+            (Some('1') | None, None) => Turn::RIGHT,
+            (Some('3'), Some(_)) => Turn::RIGHT,
+            (Some('1') | None, Some(_)) | (Some('3'), None) => Turn::LEFT,
+            _ => return Err(()),
+        };
+
+        match letter.to_ascii_uppercase() {
+            'M' | 'E' | 'S' if !wide => {
+                let (side, rotation) = match letter {
+                    'M' => (Side::LEFT, Side::LEFT),
+                    'E' => (Side::DOWN, Side::DOWN),
+                    _ => (Side::FRONT, Side::FRONT),
+                };
+                moves.push(T::from_parts(frame[side.opposite() as usize], turns));
+                moves.push(T::from_parts(frame[side as usize], turns.invert()));
+                rotate(frame, rotation, turns);
+            }
+            'X' | 'Y' | 'Z' if letter.is_ascii_lowercase() => {
+                let side = match letter {
+                    'x' => Side::RIGHT,
+                    'y' => Side::UP,
+                    _ => Side::FRONT,
+                };
+                rotate(frame, side, turns);
+            }
+            face => {
+                let side = match face {
+                    'U' => Side::UP,
+                    'D' => Side::DOWN,
+                    'F' => Side::FRONT,
+                    'B' => Side::BACK,
+                    'R' => Side::RIGHT,
+                    'L' => Side::LEFT,
+                    _ => return Err(()),
+                };
+                if wide {
+                    moves.push(T::from_parts(frame[side.opposite() as usize], turns));
+                    rotate(frame, side, turns);
+                } else {
+                    moves.push(T::from_parts(frame[side as usize], turns));
+                }
+            }
+        }
+    }
+    Ok(moves)
+}
+
+/// The moves of an algorithm as they're written, like `Rw2` or `x'`, without
+/// brackets or spaces. Each one expands on its own, given the rotations
+/// before it.
+pub fn split(algorithm: &str) -> Vec<String> {
+    let mut chars = algorithm
+        .chars()
+        .filter(|c| !c.is_whitespace() && !"()[]".contains(*c))
+        .peekable();
+    let mut moves = Vec::new();
+    while let Some(letter) = chars.next() {
+        let mut r#move = String::from(letter);
+        if !letter.is_ascii_lowercase() || "xyz".contains(letter) {
+            r#move.extend(chars.next_if_eq(&'w'));
+        }
+        r#move.extend(chars.next_if(|c| c.is_ascii_digit()));
+        r#move.extend(chars.next_if_eq(&'\''));
+        moves.push(r#move);
+    }
+    moves
+}
+
+/// Turns the whole cube the way `side` turns.
+fn rotate(frame: &mut [Side; 6], side: Side, turns: Turn) {
+    let quarter_turns = match turns {
+        Turn::RIGHT => 1,
+        Turn::TWO => 2,
+        Turn::LEFT => 3,
+    };
+    // the sides around `side`, each taking the place of the one before it
+    let cycle = match side.opposite() {
+        Side::LEFT | Side::RIGHT => [Side::UP, Side::FRONT, Side::DOWN, Side::BACK],
+        Side::UP | Side::DOWN => [Side::FRONT, Side::RIGHT, Side::BACK, Side::LEFT],
+        Side::FRONT | Side::BACK => [Side::UP, Side::LEFT, Side::DOWN, Side::RIGHT],
+    };
+    let cycle = if matches!(side, Side::RIGHT | Side::UP | Side::FRONT) {
+        cycle
+    } else {
+        [cycle[3], cycle[2], cycle[1], cycle[0]]
+    };
+    for _ in 0..quarter_turns {
+        let first = frame[cycle[0] as usize];
+        for index in 0..3 {
+            frame[cycle[index] as usize] = frame[cycle[index + 1] as usize];
+        }
+        frame[cycle[3] as usize] = first;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cube::{Cube, simple_move::SimpleMove};
+
+    fn expanded(algorithm: &str) -> String {
+        expand::<SimpleMove>(algorithm).unwrap().to_string()
+    }
+
+    #[test]
+    fn keeps_face_turns() {
+        assert_eq!(expanded("R U R' U'"), "R U R' U'");
+        assert_eq!(expanded("(R U2)(R' U')"), "R U2 R' U'");
+        assert_eq!(expanded("RUR'U'"), "R U R' U'");
+    }
+
+    #[test]
+    fn splits_moves_as_written() {
+        assert_eq!(
+            split("(R U2')(r' U') x2 Rw M2"),
+            ["R", "U2'", "r'", "U'", "x2", "Rw", "M2"]
+        );
+        assert_eq!(split("RUR'U'"), ["R", "U", "R'", "U'"]);
+    }
+
+    #[test]
+    fn rewrites_wide_moves_and_rotations() {
+        assert_eq!(expanded("r U r'"), "L F L'");
+        assert_eq!(expanded("Rw U Rw'"), "L F L'");
+        assert_eq!(expanded("y R U R' y'"), "B U B'");
+        assert_eq!(expanded("x U x'"), "F");
+        assert_eq!(expanded("z U z'"), "L");
+        assert_eq!(expanded("f R f'"), "B U B'");
+    }
+
+    #[test]
+    fn slice_moves_keep_centers_in_place() {
+        let h_perm = expand::<SimpleMove>("M2 U M2 U2 M2 U M2").unwrap();
+        let cube = Cube::create_solved().apply(&h_perm);
+        assert_eq!(
+            cube.edge_permutation(),
+            &[2, 3, 0, 1, 4, 5, 6, 7, 8, 9, 10, 11]
+        );
+        assert_eq!(cube.corner_permutation(), &[0, 1, 2, 3, 4, 5, 6, 7]);
+        assert!(cube.is_solvable());
+
+        let commutator = expand::<SimpleMove>("M' U2 M U2").unwrap();
+        let cube = Cube::create_solved().apply(&commutator);
+        assert_eq!(cube.corner_permutation(), &[0, 1, 2, 3, 4, 5, 6, 7]);
+        let moved = (0..12)
+            .filter(|&edge| cube.edge_permutation()[edge] != edge as u8)
+            .count();
+        assert_eq!(moved, 3);
+    }
+
+    #[test]
+    fn rejects_unknown_moves() {
+        assert!(expand::<SimpleMove>("R Q").is_err());
+        assert!(expand::<SimpleMove>("R4").is_err());
+    }
+}
@@ -23,7 +23,7 @@ impl Corner {
         for _ in 0..times {
             new = Self::rotate1(new);
         }
-        return new;
+        new
     }
 
     fn rotate1(corner: Self) -> Self {
@@ -1,27 +1,16 @@
 use std::{fmt::Display, str::FromStr};
 
-use rand::distr::Distribution;
+use rand::distr::{Distribution, StandardUniform};
 
 use crate::cube::{Cube, r#move::Move};
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
 pub struct SimpleMove {
     pub side: Side,
     pub turns: Turn,
 }
 
 impl SimpleMove {
-    // pub fn invert(&self) -> SimpleMove {
-    //     SimpleMove {
-    //         side: self.side.clone(),
-    //         turns: match self.turns {
-    //             Turn::RIGHT => Turn::LEFT,
-    //             Turn::LEFT => Turn::RIGHT,
-    //             Turn::TWO => Turn::TWO,
-    //         },
-    //     }
-    // }
-
     fn rotate1(&self, cube: &Cube) -> Cube {
         let corner_permutation = CORNER_PERMUTATION[self.side as usize];
         let corner_rotation = CORNER_ORIENTATION[self.side as usize];
@@ -58,6 +47,20 @@ impl Move for SimpleMove {
             self::Turn::TWO => self.rotate1(&self.rotate1(cube)),
         }
     }
+    fn side(&self) -> Side {
+        self.side
+    }
+
+    fn turns(&self) -> Turn {
+        self.turns
+    }
+
+    fn invert(&self) -> Self {
+        SimpleMove {
+            side: self.side,
+            turns: self.turns.invert(),
+        }
+    }
     fn all_moves() -> [Self; 18] {
         ALL_MOVES
     }
@@ -87,22 +90,13 @@ impl FromStr for SimpleMove {
     }
 }
 
-impl Distribution<SimpleMove> for SimpleMove {
+impl Distribution<SimpleMove> for StandardUniform {
     fn sample<R: rand::Rng + ?Sized>(&self, rng: &mut R) -> SimpleMove {
-        let side: Side = [
-            Side::UP,
-            Side::DOWN,
-            Side::FRONT,
-            Side::BACK,
-            Side::RIGHT,
-            Side::LEFT,
-        ][rng.random_range(0..6)]
-        .to_owned();
-        let turns: Turn = [Turn::LEFT, Turn::RIGHT, Turn::TWO][rand::random_range(0..3)].to_owned();
-        SimpleMove { side, turns }
+        ALL_MOVES[rng.random_range(0..ALL_MOVES.len())].clone()
     }
 }
 
+#[allow(clippy::upper_case_acronyms)]
 #[derive(Debug, Clone, PartialEq, Eq, Hash, Copy)]
 pub enum Side {
     UP = 0,
@@ -113,13 +107,60 @@ pub enum Side {
     LEFT = 5,
 }
 
-#[derive(Debug, Clone)]
+impl Side {
+    pub fn axis(self) -> u8 {
+        self as u8 / 2
+    }
+
+    pub fn opposite(self) -> Side {
+        match self {
+            Side::UP => Side::DOWN,
+            Side::DOWN => Side::UP,
+            Side::FRONT => Side::BACK,
+            Side::BACK => Side::FRONT,
+            Side::RIGHT => Side::LEFT,
+            Side::LEFT => Side::RIGHT,
+        }
+    }
+}
+
+#[allow(clippy::upper_case_acronyms)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Copy)]
 pub enum Turn {
     RIGHT = 1,
     LEFT = -1,
     TWO = 2,
 }
 
+impl Turn {
+    pub fn invert(self) -> Turn {
+        match self {
+            Turn::RIGHT => Turn::LEFT,
+            Turn::LEFT => Turn::RIGHT,
+            Turn::TWO => Turn::TWO,
+        }
+    }
+
+    /// Clockwise quarter turns, from 1 to 3.
+    pub fn quarter_turns(self) -> u8 {
+        match self {
+            Turn::RIGHT => 1,
+            Turn::TWO => 2,
+            Turn::LEFT => 3,
+        }
+    }
+
+    /// `None` when the quarter turns add up to a full rotation.
+    pub fn from_quarter_turns(quarter_turns: u8) -> Option<Turn> {
+        match quarter_turns % 4 {
+            1 => Some(Turn::RIGHT),
+            2 => Some(Turn::TWO),
+            3 => Some(Turn::LEFT),
+            _ => None,
+        }
+    }
+}
+
 impl Display for SimpleMove {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         let side = match self.side {
@@ -0,0 +1,86 @@
+use std::{fmt::Display, str::FromStr};
+
+use crate::cube::{Cube, simple_move::Turn};
+
+/// A middle layer, turning the same way as the side named after it: `M` like
+/// `L`, `E` like `D` and `S` like `F`.
+#[allow(clippy::upper_case_acronyms)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Slice {
+    M,
+    E,
+    S,
+}
+
+/// A turn of a middle layer.
+///
+/// `Cube` has no centers and tracks pieces relative to them, as
+/// `notation::expand` does, but a slice move turns the centers themselves.
+/// `apply` keeps the corners as the frame instead, which slice moves leave in
+/// place, and only moves the edges of the slice. Once the slice moves bring
+/// the centers back, the two frames agree and the pieces are exactly where
+/// `notation::expand` puts them.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct SliceMove {
+    pub slice: Slice,
+    pub turns: Turn,
+}
+
+/// The edges of each slice, each moving to the next one on a quarter turn.
+/// Every one of them gets flipped on the way.
+const EDGE_CYCLES: [[usize; 4]; 3] = [[0, 8, 10, 2], [4, 5, 6, 7], [3, 1, 9, 11]];
+
+impl SliceMove {
+    pub fn apply(&self, cube: &Cube) -> Cube {
+        let cycle = EDGE_CYCLES[self.slice as usize];
+        let mut new = cube.clone();
+        for _ in 0..self.turns.quarter_turns() {
+            let old = new.clone();
+            for index in 0..4 {
+                let (from, to) = (cycle[index], cycle[(index + 1) % 4]);
+                new.edge_permutation[to] = old.edge_permutation[from];
+                new.edge_orientation[to] = old.edge_orientation[from] ^ 1;
+            }
+        }
+        new
+    }
+
+    pub fn invert(&self) -> Self {
+        SliceMove {
+            slice: self.slice,
+            turns: self.turns.invert(),
+        }
+    }
+}
+
+impl FromStr for SliceMove {
+    type Err = ();
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut chars = s.chars();
+        let slice = match chars.next() {
+            Some('M') => Slice::M,
+            Some('E') => Slice::E,
+            Some('S') => Slice::S,
+            _ => return Err(()),
+        };
+        let turns = match chars.next() {
+            None => Turn::RIGHT,
+            Some('\'') => Turn::LEFT,
+            Some('2') => Turn::TWO,
+            _ => return Err(()),
+        };
+        Ok(SliceMove { slice, turns })
+    }
+}
+
+impl Display for SliceMove {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let turns = match self.turns {
+            Turn::RIGHT => "",
+            Turn::LEFT => "'",
+            Turn::TWO => "2",
+        };
+        write!(f, "{:?}{}", self.slice, turns)
+    }
+}
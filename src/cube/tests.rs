@@ -0,0 +1,365 @@
+use std::str::FromStr;
+
+use proptest::prelude::*;
+
+use crate::cube::{
+    Cube, fast_move::FastMove, mask::Mask, r#move::Move, move_sequence::Sequence, notation,
+    simple_move::SimpleMove, slice_move::SliceMove,
+};
+
+const SUPERFLIP: &str = "U R2 F B R B2 R U2 L B2 R U' D' R2 F R' L B2 U2 F2";
+
+fn scrambled() -> Cube {
+    let scramble: Sequence<SimpleMove> = "R U2 F' L D B2 R' U F2 D' L2 B".parse().unwrap();
+    Cube::create_solved().apply(&scramble)
+}
+
+fn every_move_has_order_four<T: Move>() {
+    let start = scrambled();
+    for r#move in T::all_moves() {
+        let mut cube = start.clone();
+        for turn in 1..=4 {
+            cube = cube.apply_move(&r#move);
+            if turn % 2 == 1 {
+                assert_ne!(cube, start, "{move:?} after {turn} turns");
+            }
+        }
+        assert_eq!(cube, start, "{move:?} after 4 turns");
+    }
+}
+
+fn move_then_inverse_is_identity<T: Move>() {
+    let start = scrambled();
+    for r#move in T::all_moves() {
+        let cube = start.apply_move(&r#move).apply_move(&r#move.invert());
+        assert_eq!(cube, start, "{move:?}");
+    }
+}
+
+fn opposite_faces_commute<T: Move + FromStr>()
+where
+    <T as FromStr>::Err: std::fmt::Debug,
+{
+    let start = scrambled();
+    for (a, b) in [("U", "D"), ("F", "B"), ("R", "L")] {
+        for a_turn in ["", "'", "2"] {
+            for b_turn in ["", "'", "2"] {
+                let a: T = format!("{a}{a_turn}").parse().unwrap();
+                let b: T = format!("{b}{b_turn}").parse().unwrap();
+                assert_eq!(
+                    start.apply_move(&a).apply_move(&b),
+                    start.apply_move(&b).apply_move(&a),
+                    "{a:?} {b:?}"
+                );
+            }
+        }
+    }
+}
+
+fn sexy_move_has_order_six<T: Move + FromStr>()
+where
+    <T as FromStr>::Err: std::fmt::Debug,
+{
+    let sexy: Sequence<T> = "R U R' U'".parse().unwrap();
+    let mut cube = Cube::create_solved();
+    for turn in 1..=6 {
+        cube = cube.apply(&sexy);
+        assert_eq!(
+            cube == Cube::create_solved(),
+            turn == 6,
+            "after {turn} repetitions"
+        );
+    }
+}
+
+fn superflip_flips_every_edge<T: Move + FromStr>()
+where
+    <T as FromStr>::Err: std::fmt::Debug,
+{
+    let superflip: Sequence<T> = SUPERFLIP.parse().unwrap();
+    let cube = Cube::create_solved().apply(&superflip);
+    let expected = Cube {
+        edge_orientation: [1; 12],
+        ..Cube::create_solved()
+    };
+    assert_eq!(cube, expected);
+    assert_eq!(cube.apply(&superflip), Cube::create_solved());
+}
+
+#[test]
+fn simple_moves_have_order_four() {
+    every_move_has_order_four::<SimpleMove>();
+}
+
+#[test]
+fn fast_moves_have_order_four() {
+    every_move_has_order_four::<FastMove>();
+}
+
+#[test]
+fn simple_move_then_inverse_is_identity() {
+    move_then_inverse_is_identity::<SimpleMove>();
+}
+
+#[test]
+fn fast_move_then_inverse_is_identity() {
+    move_then_inverse_is_identity::<FastMove>();
+}
+
+#[test]
+fn simple_opposite_faces_commute() {
+    opposite_faces_commute::<SimpleMove>();
+}
+
+#[test]
+fn fast_opposite_faces_commute() {
+    opposite_faces_commute::<FastMove>();
+}
+
+#[test]
+fn simple_sexy_move_has_order_six() {
+    sexy_move_has_order_six::<SimpleMove>();
+}
+
+#[test]
+fn fast_sexy_move_has_order_six() {
+    sexy_move_has_order_six::<FastMove>();
+}
+
+#[test]
+fn simple_superflip_flips_every_edge() {
+    superflip_flips_every_edge::<SimpleMove>();
+}
+
+#[test]
+fn fast_superflip_flips_every_edge() {
+    superflip_flips_every_edge::<FastMove>();
+}
+
+#[test]
+fn simple_and_fast_moves_agree() {
+    for simple in SimpleMove::all_moves() {
+        let fast: FastMove = simple.to_string().parse().unwrap();
+        assert_eq!(
+            scrambled().apply_move(&simple),
+            scrambled().apply_move(&fast),
+            "{simple}"
+        );
+    }
+}
+
+#[test]
+fn simple_and_fast_sequences_agree() {
+    for _ in 0..200 {
+        let simple: Sequence<SimpleMove> = Sequence::random_scramble(30);
+        let fast: Sequence<FastMove> = simple.to_string().parse().unwrap();
+        assert_eq!(
+            Cube::create_solved().apply(&simple),
+            Cube::create_solved().apply(&fast),
+            "{simple}"
+        );
+    }
+}
+
+#[test]
+fn single_twist_flip_or_swap_is_unsolvable() {
+    let mut twisted = Cube::create_solved();
+    twisted.corner_orientation[0] = 1;
+    assert!(!twisted.is_solvable());
+
+    let mut flipped = Cube::create_solved();
+    flipped.edge_orientation[0] = 1;
+    assert!(!flipped.is_solvable());
+
+    let mut swapped = Cube::create_solved();
+    swapped.edge_permutation.swap(0, 1);
+    assert!(!swapped.is_solvable());
+}
+
+fn sequence<T: Move>() -> impl Strategy<Value = Sequence<T>> {
+    prop::collection::vec(0..18usize, 0..60).prop_map(|indices| {
+        indices
+            .into_iter()
+            .map(|i| T::all_moves()[i].clone())
+            .collect()
+    })
+}
+
+proptest! {
+    #[test]
+    fn corner_orientation_sums_to_zero(scramble in sequence::<SimpleMove>()) {
+        prop_assert_eq!(Cube::create_solved().apply(&scramble).corner_twist(), 0);
+    }
+
+    #[test]
+    fn edge_flip_sum_is_even(scramble in sequence::<SimpleMove>()) {
+        prop_assert_eq!(Cube::create_solved().apply(&scramble).edge_flip(), 0);
+    }
+
+    #[test]
+    fn corner_and_edge_parities_match(scramble in sequence::<SimpleMove>()) {
+        let cube = Cube::create_solved().apply(&scramble);
+        prop_assert_eq!(cube.corner_parity(), cube.edge_parity());
+    }
+
+    #[test]
+    fn fast_moves_keep_invariants(scramble in sequence::<FastMove>()) {
+        prop_assert!(Cube::create_solved().apply(&scramble).is_solvable());
+    }
+
+    #[test]
+    fn sequence_then_inverse_is_solved(scramble in sequence::<SimpleMove>()) {
+        let cube = Cube::create_solved().apply(&scramble).apply(&scramble.invert());
+        prop_assert_eq!(cube, Cube::create_solved());
+    }
+
+    #[test]
+    fn fast_sequence_then_inverse_is_solved(scramble in sequence::<FastMove>()) {
+        let cube = Cube::create_solved().apply(&scramble).apply(&scramble.invert());
+        prop_assert_eq!(cube, Cube::create_solved());
+    }
+
+    #[test]
+    fn inverse_undoes_the_scramble(scramble in sequence::<SimpleMove>()) {
+        let cube = Cube::create_solved().apply(&scramble);
+        prop_assert_eq!(cube.inverse(), Cube::create_solved().apply(&scramble.invert()));
+        prop_assert_eq!(cube.compose(&cube.inverse()), Cube::create_solved());
+    }
+
+    #[test]
+    fn orientation_along_an_axis_keeps_to_its_moves(scramble in sequence::<SimpleMove>()) {
+        use crate::cube::simple_move::{Side, Turn};
+        let cube = Cube::create_solved().apply(&scramble);
+        prop_assert_eq!(cube.corner_orientation_along(Side::UP), *cube.corner_orientation());
+        prop_assert_eq!(cube.edge_orientation_along(Side::FRONT), *cube.edge_orientation());
+
+        for side in [Side::UP, Side::FRONT, Side::RIGHT] {
+            // Quarter turns of the axis keep the corners, and every other
+            // move keeps the edges.
+            let half_turned = |on_axis: bool| -> Sequence<SimpleMove> {
+                scramble
+                    .moves()
+                    .iter()
+                    .map(|r#move| match (r#move.side.axis() == side.axis()) == on_axis {
+                        true => r#move.clone(),
+                        false => SimpleMove { side: r#move.side, turns: Turn::TWO },
+                    })
+                    .collect()
+            };
+            let corners = Cube::create_solved().apply(&half_turned(true));
+            prop_assert_eq!(corners.corner_orientation_along(side), [0; 8]);
+            let edges = Cube::create_solved().apply(&half_turned(false));
+            prop_assert_eq!(edges.edge_orientation_along(side), [0; 12]);
+            let flipped = edges.apply_move(&SimpleMove { side, turns: Turn::RIGHT });
+            prop_assert_eq!(flipped.edge_orientation_along(side).iter().sum::<u8>(), 4);
+        }
+    }
+
+    #[test]
+    fn display_parse_round_trips(scramble in sequence::<SimpleMove>()) {
+        let parsed: Sequence<SimpleMove> = scramble.to_string().parse().unwrap();
+        prop_assert_eq!(parsed.to_string(), scramble.to_string());
+        prop_assert_eq!(Cube::create_solved().apply(&parsed), Cube::create_solved().apply(&scramble));
+    }
+}
+
+#[test]
+fn masks_ignore_unmarked_pieces() {
+    let cube = scrambled();
+    assert!(Mask::none().matches(&cube));
+    assert!(!Mask::all().matches(&cube));
+    assert!(Mask::all().matches(&Cube::create_solved()));
+
+    let down_turn: Sequence<SimpleMove> = "D2".parse().unwrap();
+    let cube = Cube::create_solved().apply(&down_turn);
+    assert!(Mask::first_two_layers().matches(&cube));
+    assert!(!Mask::all().matches(&cube));
+
+    let up_turn: Sequence<SimpleMove> = "U".parse().unwrap();
+    assert!(!Mask::cross().matches(&Cube::create_solved().apply(&up_turn)));
+
+    let sexy: Sequence<SimpleMove> = "R U R' U'".parse().unwrap();
+    assert!(Mask::edge_orientation().matches(&Cube::create_solved().apply(&sexy)));
+    let front: Sequence<SimpleMove> = "F".parse().unwrap();
+    assert!(!Mask::edge_orientation().matches(&Cube::create_solved().apply(&front)));
+}
+
+#[test]
+fn blocks_run_along_their_bottom() {
+    use crate::cube::{mask::Care, simple_move::Side};
+
+    let first_block = Mask::none()
+        .with_corners(&[6, 7], Care::Full)
+        .with_edges(&[6, 7, 11], Care::Full);
+    assert_eq!(Mask::first_block(), first_block);
+
+    let turn: Sequence<SimpleMove> = "R".parse().unwrap();
+    let cube = Cube::create_solved().apply(&turn);
+    assert!(Mask::block(Side::LEFT, Side::UP).matches(&cube));
+    assert!(!Mask::block(Side::FRONT, Side::UP).matches(&cube));
+    assert!(Mask::eo_line_on(Side::UP).matches(&cube));
+}
+
+#[test]
+fn slice_moves_agree_with_expanded_notation() {
+    for algorithm in [
+        "M2 U M2 U2 M2 U M2",
+        "M' U2 M U2",
+        "M' U M U'",
+        "E R E' R'",
+        "S U S' U'",
+        "R M' U M2 F M' B2 S L S' E D E'",
+    ] {
+        let mut cube = scrambled();
+        for name in algorithm.split_whitespace() {
+            cube = match name.parse::<SliceMove>() {
+                Ok(slice) => slice.apply(&cube),
+                Err(()) => cube.apply_move(&name.parse::<SimpleMove>().unwrap()),
+            };
+        }
+        let expanded: Sequence<SimpleMove> = notation::expand(algorithm).unwrap();
+        assert_eq!(cube, scrambled().apply(&expanded), "{algorithm}");
+    }
+}
+
+#[test]
+fn compose_applies_the_moves_of_another_cube() {
+    let sexy: Sequence<SimpleMove> = "R U R' U'".parse().unwrap();
+    let moved = Cube::create_solved().apply(&sexy);
+    assert_eq!(scrambled().compose(&moved), scrambled().apply(&sexy));
+}
+
+#[test]
+fn only_keeps_the_given_pieces() {
+    // A U perm cycles three edges and no corners.
+    let algorithm: Sequence<SimpleMove> = "R U' R U R U R U' R' U' R2".parse().unwrap();
+    let cube = Cube::create_solved().apply(&algorithm);
+    assert_eq!(cube.only(&[], &[0, 1, 3]), cube);
+    assert_eq!(cube.only(&[0, 1], &[2]), Cube::create_solved());
+}
+
+#[test]
+fn random_cubes_keep_their_mask() {
+    use rand::{SeedableRng, rngs::StdRng};
+
+    let mut rng = StdRng::seed_from_u64(7);
+    for mask in [
+        Mask::none(),
+        Mask::cross(),
+        Mask::edge_orientation(),
+        Mask::first_two_layers(),
+        Mask::eo_line(),
+    ] {
+        for _ in 0..200 {
+            let cube = Cube::random(&mut rng, &mask);
+            assert!(cube.is_solvable(), "{cube:?}");
+            assert!(mask.matches(&cube), "{cube:?}");
+        }
+    }
+    assert_eq!(Cube::random(&mut rng, &Mask::all()), Cube::create_solved());
+    let last_layer = Cube::random(&mut rng, &Mask::first_two_layers());
+    assert_ne!(
+        last_layer,
+        Cube::random(&mut rng, &Mask::first_two_layers())
+    );
+}
@@ -0,0 +1,4 @@
+pub mod cube;
+pub mod method;
+pub mod solver;
+pub mod table;
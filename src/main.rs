@@ -1,14 +1,10 @@
-mod cube;
-mod solver;
-
 use std::time::Instant;
 
-use cube::move_sequence::Sequence;
-
-use cube::Cube;
-
-use crate::cube::simple_move::SimpleMove;
-use crate::cube::fast_move::FastMove;
+use rustcubesolver::cube::move_sequence::Sequence;
+use rustcubesolver::cube::Cube;
+use rustcubesolver::cube::simple_move::SimpleMove;
+use rustcubesolver::method::algorithms::Library;
+use rustcubesolver::solver;
 
 
 
@@ -18,6 +14,12 @@ use crate::cube::fast_move::FastMove;
 type M = SimpleMove;
 
 fn main() {
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    if args.first().is_some_and(|command| command == "verify") {
+        verify(args.get(1));
+        return;
+    }
+
     // let scramble = Sequence::random_scramble(5);
     let scramble: Sequence<M> = String::from("R U R' U' R U").parse().unwrap();
     println!("Finding solution for {}", scramble);
@@ -40,3 +42,23 @@ fn print_link(scramble: &Sequence<M>, solution: &Sequence<M>) {
     let alg: String = solution.to_string().replace("'", "-").replace(" ", "_");
     println!("https://alg.cubing.net/?setup={setup}&alg={alg}");
 }
+
+/// Checks every algorithm of a text or JSON alg sheet, or the built-in ones.
+fn verify(path: Option<&String>) {
+    let library = match path {
+        Some(path) => Library::load(path).unwrap_or_else(|error| {
+            eprintln!("Couldn't read {path}: {error}");
+            std::process::exit(2);
+        }),
+        None => Library::built_in(),
+    };
+    let problems = library.verify();
+    for (algorithm, problem) in &problems {
+        println!("{algorithm}\n    {problem}");
+    }
+    let total = library.algorithms.len();
+    println!("{} of {total} algorithms verified", total - problems.len());
+    if !problems.is_empty() {
+        std::process::exit(1);
+    }
+}
@@ -0,0 +1,145 @@
+pub mod algorithms;
+pub mod bld;
+pub mod cfop;
+pub mod fmc;
+pub mod generator;
+pub mod inspection;
+pub mod patterns;
+pub mod recognition;
+pub mod reconstruction;
+pub mod roux;
+pub mod scramble;
+pub mod zz;
+
+use std::fmt::Display;
+
+use crate::{
+    cube::{
+        Cube,
+        metric::Metric,
+        r#move::Move,
+        move_sequence::Sequence,
+        notation,
+        simple_move::{Side, Turn},
+    },
+    method::recognition::Case,
+};
+
+/// One labeled step of a human-style solution.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Stage<T: Move> {
+    pub name: String,
+    /// The stage as a solver would write it, which can include slice moves.
+    pub notation: String,
+    /// The stage in face turns.
+    pub moves: Sequence<T>,
+}
+
+/// A solution split into the stages of a speedsolving method.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Solution<T: Move> {
+    pub stages: Vec<Stage<T>>,
+}
+
+impl<T: Move> Stage<T> {
+    /// The moves of the stage as solvers count them, in `STM`.
+    pub fn length(&self) -> usize {
+        self.length_in(Metric::STM)
+    }
+
+    /// The moves of the stage as written, counted in `metric`.
+    pub fn length_in(&self, metric: Metric) -> usize {
+        metric.count(&self.notation)
+    }
+}
+
+impl<T: Move> Solution<T> {
+    /// Every stage one after the other.
+    pub fn moves(&self) -> Sequence<T> {
+        self.stages
+            .iter()
+            .fold(Sequence::new(), |moves, stage| moves.concat(&stage.moves))
+    }
+}
+
+impl<T: Move> Display for Solution<T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        for stage in &self.stages {
+            writeln!(
+                f,
+                "{} // {} ({})",
+                stage.notation,
+                stage.name,
+                stage.length()
+            )?;
+        }
+        Ok(())
+    }
+}
+
+/// The rotation that holds the cube with `side` at the bottom, so algorithms
+/// written for a solved bottom layer apply to a first layer on `side`.
+pub(crate) fn hold(side: Side) -> &'static str {
+    match side {
+        Side::DOWN => "",
+        Side::UP => "x2",
+        Side::FRONT => "x'",
+        Side::BACK => "x",
+        Side::RIGHT => "z",
+        Side::LEFT => "z'",
+    }
+}
+
+/// The shortest way to reach `goal` with one of `algorithms`, or none of them,
+/// after turning the last layer into place, and also after it when `adjust`.
+pub(crate) fn last_layer_stage<T: Move>(
+    cube: &Cube,
+    side: Side,
+    algorithms: &[(&'static str, &'static str)],
+    goal: impl Fn(&Cube) -> bool,
+    adjust: bool,
+) -> Option<Case<T>> {
+    let last_layer = side.opposite();
+    let turns: Vec<(&str, Sequence<T>)> = [
+        ("", None),
+        ("U", Some(Turn::RIGHT)),
+        ("U2", Some(Turn::TWO)),
+        ("U'", Some(Turn::LEFT)),
+    ]
+    .into_iter()
+    .map(|(name, turns)| {
+        let moves = turns
+            .map(|turns| T::from_parts(last_layer, turns))
+            .into_iter()
+            .collect();
+        (name, moves)
+    })
+    .collect();
+    let after = if adjust { &turns[..] } else { &turns[..1] };
+
+    let mut best: Option<Case<T>> = None;
+    let skip = ("skip", "");
+    for &(name, algorithm) in std::iter::once(&skip).chain(algorithms) {
+        let expanded: Sequence<T> =
+            notation::expand(&format!("{} {algorithm}", hold(side))).ok()?;
+        for (before_name, before) in &turns {
+            for (after_name, after) in after {
+                let moves = before.concat(&expanded).concat(after).simplify();
+                let shorter = best
+                    .as_ref()
+                    .is_none_or(|best| moves.len() < best.moves.len());
+                if shorter && goal(&cube.apply(&moves)) {
+                    let parts = [hold(side), before_name, algorithm, after_name];
+                    let parts: Vec<&str> =
+                        parts.into_iter().filter(|part| !part.is_empty()).collect();
+                    best = Some(Case {
+                        name,
+                        algorithm: parts.join(" "),
+                        moves,
+                    });
+                }
+            }
+        }
+    }
+    best
+}
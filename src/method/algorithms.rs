@@ -0,0 +1,468 @@
+use std::{fmt::Display, fs, io, path::Path, str::FromStr, sync::LazyLock};
+
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    cube::{
+        Cube,
+        mask::{Care, Mask},
+        r#move::Move,
+        move_sequence::Sequence,
+        notation,
+        simple_move::{Side, SimpleMove, Turn},
+    },
+    method::{
+        cfop::{OLL, PLL},
+        last_layer_stage,
+        zz::OCLL,
+    },
+};
+
+const LAST_LAYER_CORNERS: [usize; 4] = [0, 1, 2, 3];
+const LAST_LAYER_EDGES: [usize; 4] = [0, 1, 2, 3];
+
+/// The sets too large to write out in code, one algorithm file each.
+static COLL: LazyLock<Vec<(&str, &str)>> =
+    LazyLock::new(|| built_in_file(Set::COLL, include_str!("algorithms/coll.txt")));
+static ZBLL: LazyLock<Vec<(&str, &str)>> =
+    LazyLock::new(|| built_in_file(Set::ZBLL, include_str!("algorithms/zbll.txt")));
+static CMLL: LazyLock<Vec<(&str, &str)>> =
+    LazyLock::new(|| built_in_file(Set::CMLL, include_str!("algorithms/cmll.txt")));
+static WV: LazyLock<Vec<(&str, &str)>> =
+    LazyLock::new(|| built_in_file(Set::WV, include_str!("algorithms/wv.txt")));
+
+/// The kinds of cases an algorithm can be written for, all with the last
+/// layer on `U`.
+#[allow(clippy::upper_case_acronyms)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum Set {
+    OLL,
+    PLL,
+    /// Orients the corners once the edges are.
+    OCLL,
+    /// Solves the corners once the edges are oriented.
+    COLL,
+    /// Solves the last layer once the edges are oriented.
+    ZBLL,
+    /// Solves the corners with the two Roux blocks built.
+    CMLL,
+    /// Winter Variation, inserts the last pair at `FR` while orienting the
+    /// corners.
+    WV,
+}
+
+impl Set {
+    pub const ALL: [Set; 7] = [
+        Set::OLL,
+        Set::PLL,
+        Set::OCLL,
+        Set::COLL,
+        Set::ZBLL,
+        Set::CMLL,
+        Set::WV,
+    ];
+
+    /// What every case of the set has solved.
+    pub fn keeps(self) -> Mask {
+        let f2l = Mask::first_two_layers_on(Side::DOWN);
+        let oriented_edges = f2l.clone().with_edges(&LAST_LAYER_EDGES, Care::Orientation);
+        match self {
+            Set::OLL => f2l,
+            Set::PLL => oriented_edges.with_corners(&LAST_LAYER_CORNERS, Care::Orientation),
+            Set::OCLL | Set::COLL | Set::ZBLL => oriented_edges,
+            Set::CMLL => Mask::first_block().union(&Mask::second_block()),
+            Set::WV => oriented_edges
+                .with_corners(&[4], Care::Ignore)
+                .with_edges(&[4], Care::Ignore),
+        }
+    }
+
+    /// What an algorithm of the set solves, up to a turn of `U` for the sets
+    /// where `auf` says so.
+    pub fn goal(self) -> Mask {
+        let f2l = Mask::first_two_layers_on(Side::DOWN);
+        let oriented = f2l
+            .clone()
+            .with_corners(&LAST_LAYER_CORNERS, Care::Orientation)
+            .with_edges(&LAST_LAYER_EDGES, Care::Orientation);
+        match self {
+            Set::OLL | Set::OCLL | Set::WV => oriented,
+            Set::PLL | Set::ZBLL => Mask::all(),
+            Set::COLL => oriented.with_corners(&LAST_LAYER_CORNERS, Care::Full),
+            Set::CMLL => self.keeps().with_corners(&LAST_LAYER_CORNERS, Care::Full),
+        }
+    }
+
+    /// Whether the last layer may still need a turn after the algorithm.
+    pub fn auf(self) -> bool {
+        matches!(self, Set::PLL | Set::COLL | Set::ZBLL | Set::CMLL)
+    }
+
+    /// The built-in algorithms of the set, one for each of its cases, which
+    /// they name.
+    pub fn built_in(self) -> &'static [(&'static str, &'static str)] {
+        match self {
+            Set::OLL => &OLL,
+            Set::PLL => &PLL,
+            Set::OCLL => &OCLL,
+            Set::COLL => &COLL,
+            Set::ZBLL => &ZBLL,
+            Set::CMLL => &CMLL,
+            Set::WV => &WV,
+        }
+    }
+
+    fn solves(self, cube: &Cube) -> bool {
+        let goal = self.goal();
+        let turns = if self.auf() { 4 } else { 1 };
+        (0..turns).any(|turns| {
+            let adjusted = Turn::from_quarter_turns(turns).map(|turns| {
+                cube.apply_move(&SimpleMove {
+                    side: Side::UP,
+                    turns,
+                })
+            });
+            goal.matches(adjusted.as_ref().unwrap_or(cube))
+        })
+    }
+}
+
+impl FromStr for Set {
+    type Err = ();
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Set::ALL
+            .into_iter()
+            .find(|set| set.to_string().eq_ignore_ascii_case(s))
+            .ok_or(())
+    }
+}
+
+impl Display for Set {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{self:?}")
+    }
+}
+
+/// A named algorithm for one case of a set.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Algorithm {
+    pub set: Set,
+    pub name: String,
+    /// The moves as they're written, with wide moves, slices and rotations.
+    pub notation: String,
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    pub notes: String,
+}
+
+impl Algorithm {
+    /// The algorithm in face turns, or `None` if its notation has a move that
+    /// isn't understood.
+    pub fn moves<T: Move>(&self) -> Option<Sequence<T>> {
+        notation::expand(&self.notation).ok()
+    }
+
+    /// The cube the algorithm solves.
+    pub fn case(&self) -> Option<Cube> {
+        let moves: Sequence<SimpleMove> = self.moves()?;
+        Some(Cube::create_solved().apply(&moves.invert()))
+    }
+
+    /// Checks that the algorithm is one its set could have: that it keeps
+    /// what the set's cases have solved, that there is something for it to
+    /// solve, and when it's named after a built-in case, that it solves that
+    /// case.
+    pub fn verify(&self) -> Result<(), Problem> {
+        let case = self.case().ok_or(Problem::Notation)?;
+        if !self.set.keeps().matches(&case) {
+            return Err(Problem::Disturbs);
+        }
+        if self.set.solves(&case) {
+            return Err(Problem::NothingToSolve);
+        }
+        let built_in = self.set.built_in();
+        if built_in.iter().any(|(name, _)| *name == self.name) {
+            let goal = self.set.goal();
+            let recognized: Option<_> = last_layer_stage::<SimpleMove>(
+                &case,
+                Side::DOWN,
+                built_in,
+                |cube| goal.matches(cube),
+                self.set.auf(),
+            );
+            match recognized {
+                Some(recognized) if recognized.name == self.name => {}
+                recognized => return Err(Problem::Solves(recognized.map(|case| case.name))),
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Written like a line of an algorithm file.
+impl Display for Algorithm {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{} {}: {}", self.set, self.name, self.notation)?;
+        if !self.notes.is_empty() {
+            write!(f, " # {}", self.notes)?;
+        }
+        Ok(())
+    }
+}
+
+/// Why an algorithm doesn't fit its set.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Problem {
+    /// The notation has a move that isn't understood.
+    Notation,
+    /// The algorithm disturbs pieces every case of its set has solved.
+    Disturbs,
+    /// The algorithm's case is already solved.
+    NothingToSolve,
+    /// The algorithm solves another built-in case than it's named after, or
+    /// none of them.
+    Solves(Option<&'static str>),
+}
+
+impl Display for Problem {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Problem::Notation => write!(f, "notation has a move that isn't understood"),
+            Problem::Disturbs => write!(f, "disturbs pieces that should stay solved"),
+            Problem::NothingToSolve => write!(f, "leaves nothing to solve"),
+            Problem::Solves(Some(name)) => write!(f, "solves case {name}"),
+            Problem::Solves(None) => write!(f, "solves none of the known cases"),
+        }
+    }
+}
+
+/// A collection of algorithms, like an alg sheet.
+///
+/// As text, each line holds one algorithm, written `<set> <name>: <moves>`
+/// with optional notes after a `#`, like `PLL T: R U R' U' R' F R2 U' R' U'
+/// R U R' F'`. Empty lines and lines starting with `#` are skipped. As JSON,
+/// it's a list of objects with `set`, `name`, `notation` and optional `notes`.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Library {
+    pub algorithms: Vec<Algorithm>,
+}
+
+impl Library {
+    /// The algorithms the solvers use.
+    pub fn built_in() -> Self {
+        let algorithms = Set::ALL
+            .into_iter()
+            .flat_map(|set| {
+                set.built_in()
+                    .iter()
+                    .map(move |(name, notation)| Algorithm {
+                        set,
+                        name: name.to_string(),
+                        notation: notation.to_string(),
+                        notes: String::new(),
+                    })
+            })
+            .collect();
+        Library { algorithms }
+    }
+
+    /// Reads a JSON file when the path ends in `.json`, text otherwise.
+    pub fn load(path: impl AsRef<Path>) -> Result<Self, LoadError> {
+        let path = path.as_ref();
+        let contents = fs::read_to_string(path).map_err(LoadError::Io)?;
+        if path
+            .extension()
+            .is_some_and(|extension| extension == "json")
+        {
+            Library::from_json(&contents)
+        } else {
+            contents.parse()
+        }
+    }
+
+    pub fn from_json(json: &str) -> Result<Self, LoadError> {
+        let algorithms = serde_json::from_str(json).map_err(LoadError::Json)?;
+        Ok(Library { algorithms })
+    }
+
+    pub fn to_json(&self) -> String {
+        serde_json::to_string_pretty(&self.algorithms).expect("algorithms serialize")
+    }
+
+    pub fn get(&self, set: Set, name: &str) -> Option<&Algorithm> {
+        self.algorithms
+            .iter()
+            .find(|algorithm| algorithm.set == set && algorithm.name == name)
+    }
+
+    pub fn set(&self, set: Set) -> impl Iterator<Item = &Algorithm> {
+        self.algorithms
+            .iter()
+            .filter(move |algorithm| algorithm.set == set)
+    }
+
+    /// Every algorithm that doesn't fit its set, with what's wrong with it.
+    pub fn verify(&self) -> Vec<(&Algorithm, Problem)> {
+        self.algorithms
+            .iter()
+            .filter_map(|algorithm| algorithm.verify().err().map(|problem| (algorithm, problem)))
+            .collect()
+    }
+}
+
+impl FromStr for Library {
+    type Err = LoadError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut algorithms = Vec::new();
+        for (index, line) in s.lines().enumerate() {
+            let Some(line) = parse_line(line) else {
+                continue;
+            };
+            let (set, name, notation, notes) = line.map_err(|()| LoadError::Line(index + 1))?;
+            algorithms.push(Algorithm {
+                set,
+                name: name.to_string(),
+                notation: notation.to_string(),
+                notes: notes.to_string(),
+            });
+        }
+        Ok(Library { algorithms })
+    }
+}
+
+/// The set, name, moves and notes of an algorithm, as written on its line.
+type Line<'a> = (Set, &'a str, &'a str, &'a str);
+
+/// Splits a line of an algorithm file into the set, name, moves and notes of
+/// its algorithm. `None` for empty lines and comments, an error when the line
+/// isn't an algorithm.
+fn parse_line(line: &str) -> Option<Result<Line<'_>, ()>> {
+    let line = line.trim();
+    if line.is_empty() || line.starts_with('#') {
+        return None;
+    }
+    let (algorithm, notes) = line.split_once('#').unwrap_or((line, ""));
+    let parts = algorithm.split_once(':').and_then(|(label, notation)| {
+        let (set, name) = label.trim().split_once(' ')?;
+        Some((
+            set.parse().ok()?,
+            name.trim(),
+            notation.trim(),
+            notes.trim(),
+        ))
+    });
+    Some(parts.ok_or(()))
+}
+
+/// The algorithms of a file shipped with the crate, all of them for `set`.
+fn built_in_file(set: Set, text: &'static str) -> Vec<(&'static str, &'static str)> {
+    text.lines()
+        .filter_map(parse_line)
+        .map(|line| match line {
+            Ok((algorithm_set, name, notation, _)) if algorithm_set == set => (name, notation),
+            _ => panic!("built-in {set} algorithms are malformed"),
+        })
+        .collect()
+}
+
+impl Display for Library {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        for algorithm in &self.algorithms {
+            writeln!(f, "{algorithm}")?;
+        }
+        Ok(())
+    }
+}
+
+#[derive(Debug)]
+pub enum LoadError {
+    Io(io::Error),
+    Json(serde_json::Error),
+    /// The line, counting from 1, that isn't an algorithm.
+    Line(usize),
+}
+
+impl Display for LoadError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            LoadError::Io(error) => write!(f, "{error}"),
+            LoadError::Json(error) => write!(f, "{error}"),
+            LoadError::Line(line) => write!(f, "line {line} isn't `<set> <name>: <moves>`"),
+        }
+    }
+}
+
+impl std::error::Error for LoadError {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn built_in_algorithms_verify() {
+        let library = Library::built_in();
+        let counts = Set::ALL.map(|set| library.set(set).count());
+        assert_eq!(counts, [57, 21, 7, 42, 472, 42, 27]);
+        assert_eq!(library.verify(), []);
+
+        // The sets from files are picked for speed, which keeps B out.
+        for set in [Set::COLL, Set::ZBLL, Set::CMLL, Set::WV] {
+            for (name, algorithm) in set.built_in() {
+                assert!(!algorithm.contains('B'), "{set:?} {name}: {algorithm}");
+            }
+        }
+    }
+
+    #[test]
+    fn reads_text_and_json() {
+        let text = "# my sheet\n\nPLL T: R U R' U' R' F R2 U' R' U' R U R' F' # the easy one\nOLL 27: R U R' U R U2 R'\n";
+        let library: Library = text.parse().unwrap();
+        let t = library.get(Set::PLL, "T").unwrap();
+        assert_eq!(t.notes, "the easy one");
+        assert_eq!(
+            library.to_string(),
+            text.lines()
+                .skip(2)
+                .map(|line| format!("{line}\n"))
+                .collect::<String>()
+        );
+        assert_eq!(Library::from_json(&library.to_json()).unwrap(), library);
+
+        assert!(matches!(
+            "PLL T R U R'".parse::<Library>(),
+            Err(LoadError::Line(1))
+        ));
+        assert!(matches!(
+            "XLL T: R".parse::<Library>(),
+            Err(LoadError::Line(1))
+        ));
+    }
+
+    #[test]
+    fn catches_typos() {
+        let library: Library = "PLL T: R U R' U' R' F R2 U' R' U' R U R' F'
+PLL T: R U R' U R' F R2 U' R' U' R U R' F'
+PLL Jb: R U R' U' R' F R2 U' R' U' R U R' F'
+OLL 27: R U R' U R U2 R' U2
+OLL 27: R U R' U R U2 Q'
+OLL Sune: R U R' U R U2 R'
+PLL AUF: U2"
+            .parse()
+            .unwrap();
+        let problems: Vec<Problem> = library
+            .verify()
+            .into_iter()
+            .map(|(_, problem)| problem)
+            .collect();
+        assert_eq!(
+            problems,
+            [
+                Problem::Disturbs,
+                Problem::Solves(Some("T")),
+                Problem::Notation,
+                Problem::NothingToSolve,
+            ]
+        );
+    }
+}
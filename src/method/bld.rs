@@ -0,0 +1,390 @@
+pub mod swaps;
+pub mod three_style;
+
+use std::fmt::Display;
+
+use crate::cube::{
+    CORNER_SIDES, Cube, EDGE_SIDES,
+    simple_move::Side::{self, *},
+};
+
+/// The corner stickers in the order schemes letter them: `U`, `L`, `F`, `R`,
+/// `B`, then `D`, each face clockwise from its top left. The first side is the
+/// sticker's own.
+const CORNER_STICKERS: [[Side; 3]; 24] = [
+    [UP, BACK, LEFT],
+    [UP, BACK, RIGHT],
+    [UP, FRONT, RIGHT],
+    [UP, FRONT, LEFT],
+    [LEFT, UP, BACK],
+    [LEFT, UP, FRONT],
+    [LEFT, DOWN, FRONT],
+    [LEFT, DOWN, BACK],
+    [FRONT, UP, LEFT],
+    [FRONT, UP, RIGHT],
+    [FRONT, DOWN, RIGHT],
+    [FRONT, DOWN, LEFT],
+    [RIGHT, UP, FRONT],
+    [RIGHT, UP, BACK],
+    [RIGHT, DOWN, BACK],
+    [RIGHT, DOWN, FRONT],
+    [BACK, UP, RIGHT],
+    [BACK, UP, LEFT],
+    [BACK, DOWN, LEFT],
+    [BACK, DOWN, RIGHT],
+    [DOWN, FRONT, LEFT],
+    [DOWN, FRONT, RIGHT],
+    [DOWN, BACK, RIGHT],
+    [DOWN, BACK, LEFT],
+];
+
+/// The edge stickers in the same order, each face clockwise from its top.
+const EDGE_STICKERS: [[Side; 2]; 24] = [
+    [UP, BACK],
+    [UP, RIGHT],
+    [UP, FRONT],
+    [UP, LEFT],
+    [LEFT, UP],
+    [LEFT, FRONT],
+    [LEFT, DOWN],
+    [LEFT, BACK],
+    [FRONT, UP],
+    [FRONT, RIGHT],
+    [FRONT, DOWN],
+    [FRONT, LEFT],
+    [RIGHT, UP],
+    [RIGHT, BACK],
+    [RIGHT, DOWN],
+    [RIGHT, FRONT],
+    [BACK, UP],
+    [BACK, LEFT],
+    [BACK, DOWN],
+    [BACK, RIGHT],
+    [DOWN, FRONT],
+    [DOWN, RIGHT],
+    [DOWN, BACK],
+    [DOWN, LEFT],
+];
+
+const SPEFFZ: &str = "ABCDEFGHIJKLMNOPQRSTUVWX";
+
+/// A sticker of a piece slot, as the slot and the index of the sticker in
+/// `CORNER_SIDES` or `EDGE_SIDES`.
+pub type Sticker = (usize, usize);
+
+/// The letters of every sticker, for memorising targets.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Scheme {
+    corners: [[char; 3]; 8],
+    edges: [[char; 2]; 12],
+}
+
+impl Scheme {
+    /// Letters the corner and the edge stickers, each given as 24 letters in
+    /// the order of Speffz: `U`, `L`, `F`, `R`, `B`, then `D`, every face
+    /// clockwise from its top left corner or its top edge. `None` unless both
+    /// have exactly 24 letters.
+    pub fn new(corners: &str, edges: &str) -> Option<Self> {
+        let corners: Vec<char> = corners.chars().filter(|c| !c.is_whitespace()).collect();
+        let edges: Vec<char> = edges.chars().filter(|c| !c.is_whitespace()).collect();
+        if corners.len() != 24 || edges.len() != 24 {
+            return None;
+        }
+        let mut scheme = Scheme {
+            corners: [[' '; 3]; 8],
+            edges: [[' '; 2]; 12],
+        };
+        for (sides, letter) in CORNER_STICKERS.iter().zip(corners) {
+            let (slot, sticker) = find(&CORNER_SIDES, sides);
+            scheme.corners[slot][sticker] = letter;
+        }
+        for (sides, letter) in EDGE_STICKERS.iter().zip(edges) {
+            let (slot, sticker) = find(&EDGE_SIDES, sides);
+            scheme.edges[slot][sticker] = letter;
+        }
+        Some(scheme)
+    }
+
+    /// The scheme most solvers use, `A` to `X` for both corners and edges.
+    pub fn speffz() -> Self {
+        Scheme::new(SPEFFZ, SPEFFZ).expect("Speffz has 24 letters")
+    }
+
+    pub fn corner(&self, (slot, sticker): Sticker) -> char {
+        self.corners[slot][sticker]
+    }
+
+    pub fn edge(&self, (slot, sticker): Sticker) -> char {
+        self.edges[slot][sticker]
+    }
+
+    /// The corner sticker with `letter`.
+    pub fn corner_sticker(&self, letter: char) -> Option<Sticker> {
+        stickers(&self.corners).find(|&(slot, sticker)| self.corners[slot][sticker] == letter)
+    }
+
+    /// The edge sticker with `letter`.
+    pub fn edge_sticker(&self, letter: char) -> Option<Sticker> {
+        stickers(&self.edges).find(|&(slot, sticker)| self.edges[slot][sticker] == letter)
+    }
+}
+
+impl Default for Scheme {
+    fn default() -> Self {
+        Scheme::speffz()
+    }
+}
+
+/// The slot with the sides of `sides`, and where its first side is.
+fn find<const N: usize>(slots: &[[Side; N]], sides: &[Side; N]) -> Sticker {
+    slots
+        .iter()
+        .enumerate()
+        .find_map(|(slot, slot_sides)| {
+            let sticker = slot_sides.iter().position(|side| *side == sides[0])?;
+            sides
+                .iter()
+                .all(|side| slot_sides.contains(side))
+                .then_some((slot, sticker))
+        })
+        .expect("every sticker has a slot")
+}
+
+/// Every sticker, in the order of the scheme's letters.
+fn stickers<const N: usize>(letters: &[[char; N]]) -> impl Iterator<Item = Sticker> {
+    let mut stickers: Vec<(char, Sticker)> = letters
+        .iter()
+        .enumerate()
+        .flat_map(|(slot, letters)| {
+            letters
+                .iter()
+                .enumerate()
+                .map(move |(sticker, &letter)| (letter, (slot, sticker)))
+        })
+        .collect();
+    stickers.sort();
+    stickers.into_iter().map(|(_, sticker)| sticker)
+}
+
+/// What a blindfolded solver memorises for a cube: the stickers the buffer
+/// pieces are shot to, in order, and the pieces left in their slot but
+/// twisted or flipped.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Memo {
+    pub edges: Vec<char>,
+    pub corners: Vec<char>,
+    /// The sticker each flipped edge's `U` or `D` sticker, or `F` or `B` for
+    /// the middle layer, is on.
+    pub flipped: Vec<char>,
+    /// The sticker each twisted corner's `U` or `D` sticker is on.
+    pub twisted: Vec<char>,
+}
+
+impl Memo {
+    /// Traces the corners from the buffer `corner_buffer` and the edges from
+    /// `edge_buffer`, both slots of `Cube`. Each target is the sticker the
+    /// one in the buffer's first sticker belongs on. When the buffer gets its
+    /// own piece back while other pieces are still out of place, the cycle is
+    /// broken into the first of them in the scheme's order.
+    pub fn new(cube: &Cube, scheme: &Scheme, corner_buffer: usize, edge_buffer: usize) -> Self {
+        let corners = Pieces {
+            permutation: cube.corner_permutation().to_vec(),
+            orientation: cube.corner_orientation().to_vec(),
+            twists: 3,
+        };
+        let (corners, twisted) = corners.trace(corner_buffer, stickers(&scheme.corners), false);
+        let edges = Pieces {
+            permutation: cube.edge_permutation().to_vec(),
+            orientation: cube.edge_orientation().to_vec(),
+            twists: 2,
+        };
+        let (edges, flipped) = edges.trace(edge_buffer, stickers(&scheme.edges), false);
+        Memo {
+            edges: edges
+                .into_iter()
+                .map(|target| scheme.edge(target))
+                .collect(),
+            corners: corners
+                .into_iter()
+                .map(|target| scheme.corner(target))
+                .collect(),
+            flipped: flipped
+                .into_iter()
+                .map(|target| scheme.edge(target))
+                .collect(),
+            twisted: twisted
+                .into_iter()
+                .map(|target| scheme.corner(target))
+                .collect(),
+        }
+    }
+
+    /// With Speffz and the buffers of 3-style, `UFR` and `UF`.
+    pub fn speffz(cube: &Cube) -> Self {
+        Memo::new(cube, &Scheme::speffz(), 0, 0)
+    }
+
+    /// Whether the corners take an odd number of targets, which leaves two
+    /// edges and two corners swapped at the end.
+    pub fn parity(&self) -> bool {
+        self.corners.len() % 2 == 1
+    }
+}
+
+/// The targets in pairs, like `AB CD E`.
+fn pairs(letters: &[char]) -> String {
+    let pairs: Vec<String> = letters
+        .chunks(2)
+        .map(|pair| pair.iter().collect())
+        .collect();
+    pairs.join(" ")
+}
+
+impl Display for Memo {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        writeln!(f, "Edges: {}", pairs(&self.edges))?;
+        writeln!(f, "Corners: {}", pairs(&self.corners))?;
+        if !self.flipped.is_empty() {
+            writeln!(f, "Flipped: {}", pairs(&self.flipped))?;
+        }
+        if !self.twisted.is_empty() {
+            writeln!(f, "Twisted: {}", pairs(&self.twisted))?;
+        }
+        if self.parity() {
+            writeln!(f, "Parity")?;
+        }
+        Ok(())
+    }
+}
+
+/// One kind of piece, like the arrays of `Cube`.
+struct Pieces {
+    permutation: Vec<u8>,
+    orientation: Vec<u8>,
+    twists: u8,
+}
+
+impl Pieces {
+    /// The sticker that the one on `sticker` belongs on.
+    fn home(&self, (slot, sticker): Sticker) -> Sticker {
+        let twist = (sticker as u8 + self.twists - self.orientation[slot]) % self.twists;
+        (self.permutation[slot] as usize, twist as usize)
+    }
+
+    /// Swaps the buffer's piece into `target` so its first sticker lands on
+    /// the target, the way a blindfolded solver does.
+    fn swap(&mut self, buffer: usize, (slot, sticker): Sticker) {
+        let sticker = sticker as u8;
+        let shot = (self.orientation[buffer] + sticker) % self.twists;
+        let back = (self.orientation[slot] + self.twists - sticker) % self.twists;
+        self.permutation.swap(buffer, slot);
+        self.orientation[slot] = shot;
+        self.orientation[buffer] = back;
+    }
+
+    /// The targets from `buffer` and the stickers the first sticker of every
+    /// piece left twisted in its slot is on. With `in_place`, cycles are also
+    /// broken into twisted pieces, the way beginners solve them, so none are
+    /// left.
+    fn trace(
+        mut self,
+        buffer: usize,
+        order: impl Iterator<Item = Sticker>,
+        in_place: bool,
+    ) -> (Vec<Sticker>, Vec<Sticker>) {
+        let order: Vec<Sticker> = order.collect();
+        let mut targets = Vec::new();
+        loop {
+            let target = if self.permutation[buffer] as usize != buffer {
+                self.home((buffer, 0))
+            } else {
+                let misplaced = order.iter().find(|&&(slot, _)| {
+                    slot != buffer
+                        && (self.permutation[slot] as usize != slot
+                            || in_place && self.orientation[slot] != 0)
+                });
+                match misplaced {
+                    Some(&target) => target,
+                    None => break,
+                }
+            };
+            targets.push(target);
+            self.swap(buffer, target);
+        }
+
+        let mut twisted: Vec<Sticker> = (0..self.permutation.len())
+            .filter(|&slot| slot != buffer && self.orientation[slot] != 0)
+            .map(|slot| (slot, self.orientation[slot] as usize))
+            .collect();
+        twisted.sort_by_key(|twisted| order.iter().position(|sticker| sticker == twisted));
+        (targets, twisted)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cube::scrambled;
+
+    #[test]
+    fn letters_stickers_like_speffz() {
+        let scheme = Scheme::speffz();
+        assert_eq!(scheme.corner((0, 0)), 'C');
+        assert_eq!(scheme.corner((0, 1)), 'M');
+        assert_eq!(scheme.corner((0, 2)), 'J');
+        assert_eq!(scheme.edge((0, 0)), 'C');
+        assert_eq!(scheme.edge((0, 1)), 'I');
+        assert_eq!(scheme.edge((4, 0)), 'J');
+        assert_eq!(scheme.edge_sticker('P'), Some((4, 1)));
+        assert!(Scheme::new("ABC", SPEFFZ).is_none());
+    }
+
+    #[test]
+    fn solved_cube_has_nothing_to_memorise() {
+        let memo = Memo::speffz(&Cube::create_solved());
+        assert!(memo.edges.is_empty() && memo.corners.is_empty());
+        assert!(memo.flipped.is_empty() && memo.twisted.is_empty());
+        assert!(!memo.parity());
+    }
+
+    #[test]
+    fn traces_cycles_and_breaks_them() {
+        // The T permutation swaps UR with UL and UFR with UBR.
+        let cube = scrambled("R U R' U' R' F R2 U' R' U' R U R' F'");
+        let scheme = Scheme::speffz();
+        let old_pochmann = Memo::new(&cube, &scheme, 2, 1);
+        assert_eq!(old_pochmann.edges, ['D']);
+        assert_eq!(old_pochmann.corners, ['B', 'C', 'B']);
+        assert!(old_pochmann.parity());
+
+        let memo = Memo::speffz(&cube);
+        assert_eq!(memo.edges, ['B', 'D', 'B']);
+        assert_eq!(memo.corners, ['B']);
+        assert_eq!(memo.to_string(), "Edges: BD B\nCorners: B\nParity\n");
+    }
+
+    #[test]
+    fn finds_twisted_and_flipped_pieces() {
+        let twist = scrambled("R' D' R D R' D' R D U D' R' D R D' R' D R U'");
+        let memo = Memo::speffz(&twist);
+        assert!(memo.corners.is_empty());
+        assert_eq!(memo.twisted.len(), 1);
+
+        let memo = Memo::new(&twist, &Scheme::speffz(), 2, 0);
+        assert_eq!(memo.twisted.len(), 2);
+
+        let superflip = scrambled("U R2 F B R B2 R U2 L B2 R U' D' R2 F R' L B2 U2 F2");
+        let memo = Memo::speffz(&superflip);
+        assert!(memo.edges.is_empty());
+        assert_eq!(memo.flipped.len(), 11);
+        assert_eq!(memo.flipped[0], 'E');
+    }
+
+    #[test]
+    fn uses_any_scheme() {
+        let cube = scrambled("R U R' U' R' F R2 U' R' U' R U R' F'");
+        let lowercase = SPEFFZ.to_lowercase();
+        let scheme = Scheme::new(&lowercase, &lowercase).unwrap();
+        assert_eq!(Memo::new(&cube, &scheme, 0, 0).edges, ['b', 'd', 'b']);
+    }
+}
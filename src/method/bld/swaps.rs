@@ -0,0 +1,258 @@
+use crate::{
+    cube::{Cube, r#move::Move, notation},
+    method::bld::{
+        Pieces, Scheme, stickers,
+        three_style::{
+            self, Construction, Execution, Kind, Locations, Step, algorithm_locations, cycle,
+            shifts,
+        },
+    },
+};
+
+/// A T perm, swapping `UR` and `UL`, and `UFR` and `UBR`.
+const T_PERM: &str = "R U R' U' R' F R2 U' R' U' R U R' F'";
+
+/// A Y perm without its first and last `F` turn, swapping `ULB` and `DFR`,
+/// and `UB` and `UL`.
+const Y_PERM: &str = "R U' R' U' R U R' F' R U R' U' R' F R";
+
+/// The beginner methods, solving one piece at a time by an algorithm that
+/// swaps the buffer with a helper piece, and set up moves bringing each
+/// target to the helper.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Method {
+    /// A T perm from `UR` to `UL` for the edges and a Y perm from `ULB` to
+    /// `DFR` for the corners.
+    OldPochmann,
+    /// `M2` from `DF` to `UB` for the edges and `R2` from `UFR` to `DRB` for
+    /// the corners.
+    M2,
+}
+
+/// How one kind of piece is solved: the buffer and the algorithm swapping it
+/// with the helper, which can also move other pieces.
+struct Swap {
+    kind: Kind,
+    buffer: usize,
+    algorithm: &'static str,
+}
+
+impl Method {
+    /// The edges, then the corners.
+    fn swaps(self) -> [Swap; 2] {
+        let (edges, corners) = match self {
+            Method::OldPochmann => ((1, T_PERM), (2, Y_PERM)),
+            Method::M2 => ((8, "M2"), (0, "R2")),
+        };
+        [
+            Swap {
+                kind: Kind::Edge,
+                buffer: edges.0,
+                algorithm: edges.1,
+            },
+            Swap {
+                kind: Kind::Corner,
+                buffer: corners.0,
+                algorithm: corners.1,
+            },
+        ]
+    }
+}
+
+/// Solves `cube` blindfolded with `method`, edges first, each step named
+/// after its target in `scheme`.
+///
+/// The targets are traced up front, as the solver memorises them, with
+/// twisted and flipped pieces solved as two targets. Each algorithm also
+/// swaps two other pieces, which every second target swaps back. Targets on
+/// those pieces are solved by a commutator next to the algorithm. When the
+/// edges take an odd number of targets, a parity step between the edges and
+/// the corners swaps the edges' extra pieces back and the corners' extra
+/// pieces, so an odd number of corner targets ends solved. `None` if some
+/// case can't be reached that way.
+pub fn solve<T: Move>(cube: &Cube, scheme: &Scheme, method: Method) -> Option<Execution<T>> {
+    let swaps = method.swaps();
+    let [edges, corners] = &swaps;
+    let edge_targets = Pieces {
+        permutation: cube.edge_permutation().to_vec(),
+        orientation: cube.edge_orientation().to_vec(),
+        twists: 2,
+    }
+    .trace(edges.buffer, stickers(&scheme.edges), true)
+    .0;
+    let corner_targets = Pieces {
+        permutation: cube.corner_permutation().to_vec(),
+        orientation: cube.corner_orientation().to_vec(),
+        twists: 3,
+    }
+    .trace(corners.buffer, stickers(&scheme.corners), true)
+    .0;
+
+    // The cube is solved as if every step only swapped the buffer with its
+    // target, followed by the extra swaps of the algorithms so far.
+    let mut side: Locations = std::array::from_fn(|index| index as u8);
+    let mut constructions = Vec::new();
+    for (swap, targets) in [(edges, &edge_targets), (corners, &corner_targets)] {
+        let kind = swap.kind;
+        let algorithm = algorithm_locations(swap.algorithm)?;
+        let helper = algorithm[kind.index((swap.buffer, 0)) as usize];
+        let helper = (0..24)
+            .map(|sticker| (sticker / kind.twists(), sticker % kind.twists()))
+            .find(|&sticker| kind.index(sticker) == helper)?;
+        let extra = compose(&exchange(kind, swap.buffer, helper), &algorithm);
+
+        if kind == Kind::Corner && edge_targets.len() % 2 == 1 {
+            // Both algorithms swap the buffers with their helpers besides the
+            // extra pieces, which leaves a PLL to do.
+            let effect = compose(&inverse(&side), &extra);
+            let both = format!("{} {}", edges.algorithm, corners.algorithm);
+            constructions.push(("Parity".to_string(), realize(&effect, &both)?));
+            side = extra;
+        }
+        for &target in targets {
+            let next = compose(&side, &extra);
+            let effect = compose(
+                &compose(&inverse(&side), &exchange(kind, swap.buffer, target)),
+                &next,
+            );
+            let name = match kind {
+                Kind::Edge => format!("Edge {}", scheme.edge(target)),
+                Kind::Corner => format!("Corner {}", scheme.corner(target)),
+            };
+            constructions.push((name, realize(&effect, swap.algorithm)?));
+            side = next;
+        }
+    }
+
+    // `M2` turns the centers, so the moves of each step depend on the ones
+    // before it.
+    let written: Vec<String> = constructions
+        .iter()
+        .map(|(_, construction)| construction.notation())
+        .collect();
+    let expanded = (0..=written.len())
+        .map(|end| notation::expand::<T>(&written[..end].join(" ")).ok())
+        .collect::<Option<Vec<_>>>()?;
+    let steps = constructions
+        .into_iter()
+        .enumerate()
+        .map(|(index, (name, construction))| Step {
+            name,
+            construction,
+            moves: expanded[index + 1].moves()[expanded[index].len()..]
+                .iter()
+                .cloned()
+                .collect(),
+        })
+        .collect();
+    Some(Execution { steps })
+}
+
+/// A construction doing `effect`: `algorithm` set up by face turns, or next
+/// to a commutator doing the rest.
+fn realize(effect: &Locations, algorithm: &str) -> Option<Construction> {
+    let shifted = shifts(effect);
+    if let Some(setup) = three_style::setup(&shifted, algorithm) {
+        return Some(Construction::Conjugate {
+            setup,
+            algorithm: algorithm.to_string(),
+        });
+    }
+    // Slice moves turn the centers, which only `algorithm` turns back.
+    let slices = notation::split(algorithm)
+        .iter()
+        .any(|r#move| r#move.starts_with(['M', 'E', 'S']));
+    if !slices && let Some(construction) = three_style::find(&shifted) {
+        return Some(construction);
+    }
+    let swap = algorithm_locations(algorithm)?;
+    let bare = Construction::Conjugate {
+        setup: String::new(),
+        algorithm: algorithm.to_string(),
+    };
+    if let Some(before) = three_style::find(&shifts(&compose(effect, &inverse(&swap)))) {
+        return Some(Construction::Chain(vec![before, bare]));
+    }
+    let after = three_style::find(&shifts(&compose(&inverse(&swap), effect)))?;
+    Some(Construction::Chain(vec![bare, after]))
+}
+
+/// The buffer's piece and the one with sticker `target` swapping places, the
+/// way the targets are traced.
+fn exchange(kind: Kind, buffer: usize, target: (usize, usize)) -> Locations {
+    let mut locations: Locations = std::array::from_fn(|index| index as u8);
+    for (from, to) in [
+        cycle(kind, (buffer, 0), target),
+        cycle(kind, target, (buffer, 0)),
+    ]
+    .concat()
+    {
+        locations[from as usize] = to;
+    }
+    locations
+}
+
+/// `first`, then `second`.
+fn compose(first: &Locations, second: &Locations) -> Locations {
+    std::array::from_fn(|index| second[first[index] as usize])
+}
+
+fn inverse(locations: &Locations) -> Locations {
+    let mut inverse = [0; 48];
+    for (from, &to) in locations.iter().enumerate() {
+        inverse[to as usize] = from as u8;
+    }
+    inverse
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cube::{scrambled, simple_move::SimpleMove};
+
+    #[test]
+    fn solves_scrambles() {
+        for method in [Method::OldPochmann, Method::M2] {
+            for scramble in [
+                "R U R' U'",
+                "F2 L' U2 B R' D2 F L2 U' R2 B' D L' F2",
+                "D2 F' R2 U L2 B' R D' F2 L U2 B R' F D2 R2 B2 U",
+                "L2 D' B2 U F2 R U' L F' D2 B R2 U2 L' B2 R",
+            ] {
+                let cube = scrambled(scramble);
+                let execution: Execution<SimpleMove> =
+                    solve(&cube, &Scheme::speffz(), method).unwrap();
+                assert_eq!(
+                    cube.apply(&execution.moves()),
+                    Cube::create_solved(),
+                    "{method:?} {scramble}\n{execution}"
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn sets_up_targets_to_the_helper() {
+        // Shooting to `UL` is the T perm alone, then `UFR` and `UBR` are
+        // swapped back by the parity and the corners.
+        let cube = scrambled(T_PERM);
+        let execution: Execution<SimpleMove> =
+            solve(&cube, &Scheme::speffz(), Method::OldPochmann).unwrap();
+        assert_eq!(execution.steps[0].name, "Edge D");
+        assert_eq!(execution.steps[0].construction.to_string(), T_PERM);
+        assert_eq!(execution.steps[1].name, "Parity");
+        assert_eq!(cube.apply(&execution.moves()), Cube::create_solved());
+    }
+
+    #[test]
+    fn solves_m2_targets_on_the_middle_slice() {
+        // `UF` and `DB` are what `M2` swaps besides the buffer and `UB`.
+        let cube = scrambled("M2 U2 M2 U2");
+        let execution: Execution<SimpleMove> = solve(&cube, &Scheme::speffz(), Method::M2).unwrap();
+        assert_eq!(
+            cube.apply(&execution.moves()),
+            Cube::create_solved(),
+            "{execution}"
+        );
+    }
+}
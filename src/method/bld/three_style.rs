@@ -0,0 +1,760 @@
+use std::{collections::HashMap, fmt::Display, sync::LazyLock};
+
+use crate::{
+    cube::{
+        Cube,
+        r#move::Move,
+        move_sequence::Sequence,
+        notation,
+        simple_move::{ALL_MOVES, SimpleMove, Turn},
+        slice_move::{Slice, SliceMove},
+    },
+    method::{
+        Solution, Stage,
+        bld::{Pieces, Scheme, Sticker, stickers},
+        cfop::PLL,
+    },
+};
+
+/// Twists the `UFR` corner clockwise and messes up the rest of `R` and `D`.
+const TWISTER: &str = "R' D' R D R' D' R D";
+
+/// Flips the `UF` edge and messes up the rest of `F` and the `E` slice.
+const FLIPPER: &str = "F E F2 E2 F";
+
+/// An algorithm as a blindfolded solver writes it.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Construction {
+    /// `[A: [B, C]]`, for `A B C B' C' A'`.
+    Commutator {
+        setup: String,
+        insert: String,
+        interchange: String,
+    },
+    /// `[A: X]`, for `A X A'`.
+    Conjugate { setup: String, algorithm: String },
+    /// Constructions one after the other.
+    Chain(Vec<Construction>),
+}
+
+impl Construction {
+    /// The construction written out move by move.
+    pub fn notation(&self) -> String {
+        let (setup, body) = match self {
+            Construction::Chain(parts) => {
+                let parts: Vec<String> = parts.iter().map(Construction::notation).collect();
+                return parts.join(" ");
+            }
+            Construction::Commutator {
+                setup,
+                insert,
+                interchange,
+            } => (
+                setup,
+                [
+                    insert.clone(),
+                    interchange.clone(),
+                    invert(insert),
+                    invert(interchange),
+                ]
+                .join(" "),
+            ),
+            Construction::Conjugate { setup, algorithm } => (setup, algorithm.clone()),
+        };
+        [setup.clone(), body, invert(setup)]
+            .into_iter()
+            .filter(|part| !part.is_empty())
+            .collect::<Vec<_>>()
+            .join(" ")
+    }
+}
+
+impl Display for Construction {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let (setup, body) = match self {
+            Construction::Chain(parts) => {
+                let parts: Vec<String> = parts.iter().map(Construction::to_string).collect();
+                return write!(f, "{}", parts.join(" "));
+            }
+            Construction::Commutator {
+                setup,
+                insert,
+                interchange,
+            } => (setup, format!("[{insert}, {interchange}]")),
+            Construction::Conjugate { setup, algorithm } => (setup, algorithm.clone()),
+        };
+        if setup.is_empty() {
+            write!(f, "{body}")
+        } else {
+            write!(f, "[{setup}: {body}]")
+        }
+    }
+}
+
+/// One step of a 3-style solve: a pair of targets, the parity or a piece
+/// fixed in place.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Step<T: Move> {
+    /// What the step solves, like `Edges AB`, `Parity CD` or `Twist J`.
+    pub name: String,
+    pub construction: Construction,
+    pub moves: Sequence<T>,
+}
+
+/// A full blindfolded solve, one step after the other.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Execution<T: Move> {
+    pub steps: Vec<Step<T>>,
+}
+
+impl<T: Move> Execution<T> {
+    /// Every step one after the other.
+    pub fn moves(&self) -> Sequence<T> {
+        self.steps
+            .iter()
+            .fold(Sequence::new(), |moves, step| moves.concat(&step.moves))
+    }
+
+    /// The steps as stages, written out move by move.
+    pub fn solution(&self) -> Solution<T> {
+        Solution {
+            stages: self
+                .steps
+                .iter()
+                .map(|step| Stage {
+                    name: step.name.clone(),
+                    notation: step.construction.notation(),
+                    moves: step.moves.clone(),
+                })
+                .collect(),
+        }
+    }
+}
+
+impl<T: Move> Display for Execution<T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        for step in &self.steps {
+            writeln!(f, "{} // {}", step.construction, step.name)?;
+        }
+        Ok(())
+    }
+}
+
+/// Solves `cube` blindfolded with 3-style from the buffers `corner_buffer`
+/// and `edge_buffer`, following the targets of `Memo::new`.
+///
+/// Every pair of targets is a 3-cycle, solved by a commutator `[B, C]` of
+/// up to three moves in `B`, conjugated by up to three setup moves. An odd
+/// number of targets is solved with a PLL swapping the last ones with the
+/// buffers, after cycling them on to other pieces if no PLL reaches them.
+/// The pieces left twisted or flipped are fixed two at a time, handing the
+/// rest of the twist on to the next one and finally the buffer.
+/// `None` if some case can't be reached that way.
+pub fn solve<T: Move>(
+    cube: &Cube,
+    scheme: &Scheme,
+    corner_buffer: usize,
+    edge_buffer: usize,
+) -> Option<Execution<T>> {
+    let corners = Pieces {
+        permutation: cube.corner_permutation().to_vec(),
+        orientation: cube.corner_orientation().to_vec(),
+        twists: 3,
+    };
+    let (corners, twisted) = corners.trace(corner_buffer, stickers(&scheme.corners), false);
+    let edges = Pieces {
+        permutation: cube.edge_permutation().to_vec(),
+        orientation: cube.edge_orientation().to_vec(),
+        twists: 2,
+    };
+    let (edges, flipped) = edges.trace(edge_buffer, stickers(&scheme.edges), false);
+
+    let mut steps = Vec::new();
+    for (kind, targets) in [(Kind::Edge, &edges), (Kind::Corner, &corners)] {
+        let buffer = match kind {
+            Kind::Edge => edge_buffer,
+            Kind::Corner => corner_buffer,
+        };
+        for pair in targets.chunks_exact(2) {
+            steps.push(pair_step(kind, scheme, buffer, pair[0], pair[1])?);
+        }
+    }
+    if corners.len() % 2 == 1 {
+        let (&edge, &corner) = (edges.last()?, corners.last()?);
+        steps.extend(parity(
+            scheme,
+            (edge_buffer, edge),
+            (corner_buffer, corner),
+        )?);
+    }
+    for (kind, pieces, buffer) in [
+        (Kind::Edge, &flipped, edge_buffer),
+        (Kind::Corner, &twisted, corner_buffer),
+    ] {
+        let twists = kind.twists();
+        let mut orientation: HashMap<usize, usize> = pieces.iter().copied().collect();
+        for (index, &(slot, _)) in pieces.iter().enumerate() {
+            let twist = orientation[&slot];
+            if twist == 0 {
+                continue;
+            }
+            let partner = pieces.get(index + 1).map_or(buffer, |&(next, _)| next);
+            let effect = [
+                rotate(kind, slot, twists - twist),
+                rotate(kind, partner, twist),
+            ]
+            .concat();
+            if let Some(next) = orientation.get_mut(&partner) {
+                *next = (*next + twist) % twists;
+            }
+            let name = match kind {
+                Kind::Edge => format!("Flip {}", scheme.edge((slot, twist))),
+                Kind::Corner => format!("Twist {}", scheme.corner((slot, twist))),
+            };
+            steps.push(step(name, &effect)?);
+        }
+    }
+    Some(Execution { steps })
+}
+
+/// The 3-cycle taking the buffer's piece to `first` and that one to `second`.
+fn pair_step<T: Move>(
+    kind: Kind,
+    scheme: &Scheme,
+    buffer: usize,
+    first: Sticker,
+    second: Sticker,
+) -> Option<Step<T>> {
+    let effect = [
+        cycle(kind, (buffer, 0), first),
+        cycle(kind, first, second),
+        cycle(kind, second, (buffer, 0)),
+    ]
+    .concat();
+    let name = format!(
+        "{} {}{}",
+        kind.name(),
+        kind.letter(scheme, first),
+        kind.letter(scheme, second)
+    );
+    step(name, &effect)
+}
+
+/// Swaps the last edge and corner targets with their buffers, each given as
+/// the buffer and the target. When no PLL reaches them, they're first cycled
+/// on to pieces one does reach.
+fn parity<T: Move>(
+    scheme: &Scheme,
+    (edge_buffer, edge): (usize, Sticker),
+    (corner_buffer, corner): (usize, Sticker),
+) -> Option<Vec<Step<T>>> {
+    let swap = |edge: Sticker, corner: Sticker| {
+        let effect = [
+            cycle(Kind::Edge, (edge_buffer, 0), edge),
+            cycle(Kind::Edge, edge, (edge_buffer, 0)),
+            cycle(Kind::Corner, (corner_buffer, 0), corner),
+            cycle(Kind::Corner, corner, (corner_buffer, 0)),
+        ]
+        .concat();
+        let name = format!("Parity {}{}", scheme.edge(edge), scheme.corner(corner));
+        step(name, &effect)
+    };
+    if let Some(swap) = swap(edge, corner) {
+        return Some(vec![swap]);
+    }
+    for edge_slot in (0..12).filter(|&slot| slot != edge_buffer && slot != edge.0) {
+        for corner_slot in (0..8).filter(|&slot| slot != corner_buffer && slot != corner.0) {
+            let (to_edge, to_corner) = ((edge_slot, 0), (corner_slot, 0));
+            let Some(swap) = swap(to_edge, to_corner) else {
+                continue;
+            };
+            return Some(vec![
+                pair_step(Kind::Edge, scheme, edge_buffer, edge, to_edge)?,
+                pair_step(Kind::Corner, scheme, corner_buffer, corner, to_corner)?,
+                swap,
+            ]);
+        }
+    }
+    None
+}
+
+/// Finds the construction for `effect` and writes it as a step.
+fn step<T: Move>(name: String, effect: &[Shift]) -> Option<Step<T>> {
+    Step::new(name, find(effect)?)
+}
+
+impl<T: Move> Step<T> {
+    pub(super) fn new(name: String, construction: Construction) -> Option<Self> {
+        let moves = notation::expand(&construction.notation()).ok()?;
+        Some(Step {
+            name,
+            construction,
+            moves,
+        })
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(super) enum Kind {
+    Corner,
+    Edge,
+}
+
+impl Kind {
+    pub(super) fn twists(self) -> usize {
+        match self {
+            Kind::Corner => 3,
+            Kind::Edge => 2,
+        }
+    }
+
+    fn name(self) -> &'static str {
+        match self {
+            Kind::Corner => "Corners",
+            Kind::Edge => "Edges",
+        }
+    }
+
+    pub(super) fn letter(self, scheme: &Scheme, sticker: Sticker) -> char {
+        match self {
+            Kind::Corner => scheme.corner(sticker),
+            Kind::Edge => scheme.edge(sticker),
+        }
+    }
+
+    /// The index of a sticker among all 48, corners first.
+    pub(super) fn index(self, (slot, sticker): Sticker) -> u8 {
+        match self {
+            Kind::Corner => (slot * 3 + sticker) as u8,
+            Kind::Edge => (24 + slot * 2 + sticker) as u8,
+        }
+    }
+}
+
+/// A sticker moving to another place, as the indices of `Kind::index`.
+pub(super) type Shift = (u8, u8);
+
+/// Where each of the 48 stickers goes, by `Kind::index`.
+pub(super) type Locations = [u8; 48];
+
+/// The piece with sticker `from` moving to `to`, its other stickers going
+/// along with it.
+pub(super) fn cycle(
+    kind: Kind,
+    (from, from_sticker): Sticker,
+    (to, to_sticker): Sticker,
+) -> Vec<Shift> {
+    let twists = kind.twists();
+    (0..twists)
+        .map(|offset| {
+            (
+                kind.index((from, (from_sticker + offset) % twists)),
+                kind.index((to, (to_sticker + offset) % twists)),
+            )
+        })
+        .collect()
+}
+
+/// The piece in `slot` turning in place by `twist` stickers.
+fn rotate(kind: Kind, slot: usize, twist: usize) -> Vec<Shift> {
+    cycle(kind, (slot, 0), (slot, twist))
+}
+
+/// Where every sticker of the solved cube is after the moves that led to
+/// `cube`, by `Kind::index`.
+pub(super) fn locations(cube: &Cube) -> Locations {
+    let mut locations = [0; 48];
+    for (kind, permutation, orientation) in [
+        (
+            Kind::Corner,
+            &cube.corner_permutation()[..],
+            &cube.corner_orientation()[..],
+        ),
+        (
+            Kind::Edge,
+            &cube.edge_permutation()[..],
+            &cube.edge_orientation()[..],
+        ),
+    ] {
+        let twists = kind.twists();
+        for (slot, (&piece, &twist)) in permutation.iter().zip(orientation).enumerate() {
+            for sticker in 0..twists {
+                let home = (piece as usize, (sticker + twists - twist as usize) % twists);
+                locations[kind.index(home) as usize] = kind.index((slot, sticker));
+            }
+        }
+    }
+    locations
+}
+
+/// The stickers `cube` moves, sorted, as a key of the table.
+fn effect(cube: &Cube) -> Vec<Shift> {
+    shifts(&locations(cube))
+}
+
+/// The stickers `locations` moves, sorted.
+pub(super) fn shifts(locations: &Locations) -> Vec<Shift> {
+    locations
+        .iter()
+        .enumerate()
+        .map(|(from, &to)| (from as u8, to))
+        .filter(|(from, to)| from != to)
+        .collect()
+}
+
+/// A move of a single layer, seen from the corners like `SliceMove`.
+struct Layer {
+    name: String,
+    cube: Cube,
+    /// The index of the layer turning back.
+    inverse: usize,
+}
+
+/// The face and slice turns.
+static LAYERS: LazyLock<Vec<Layer>> = LazyLock::new(|| {
+    let solved = Cube::create_solved();
+    let mut layers: Vec<(String, Cube)> = ALL_MOVES
+        .iter()
+        .map(|r#move| (r#move.to_string(), solved.apply_move(r#move)))
+        .collect();
+    for slice in [Slice::M, Slice::E, Slice::S] {
+        for turns in [Turn::RIGHT, Turn::LEFT, Turn::TWO] {
+            let r#move = SliceMove { slice, turns };
+            layers.push((r#move.to_string(), r#move.apply(&solved)));
+        }
+    }
+    let names: Vec<String> = layers.iter().map(|(name, _)| name.clone()).collect();
+    layers
+        .into_iter()
+        .map(|(name, cube)| {
+            let inverse = names
+                .iter()
+                .position(|other| *other == invert(&name))
+                .expect("every layer turns back");
+            Layer {
+                name,
+                cube,
+                inverse,
+            }
+        })
+        .collect()
+});
+
+/// `moves`, indices of `LAYERS`, written out.
+fn written(moves: &[usize]) -> String {
+    moves
+        .iter()
+        .map(|&index| LAYERS[index].name.as_str())
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// `algorithm` backwards, for moves written like `LAYERS`.
+fn invert(algorithm: &str) -> String {
+    algorithm
+        .split_whitespace()
+        .rev()
+        .map(|r#move| match r#move.strip_suffix('\'') {
+            Some(r#move) => r#move.to_string(),
+            None if r#move.ends_with('2') => r#move.to_string(),
+            None => format!("{move}'"),
+        })
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// The cube after `moves` from solved.
+fn turn(moves: &[usize]) -> Cube {
+    moves.iter().fold(Cube::create_solved(), |cube, &index| {
+        cube.compose(&LAYERS[index].cube)
+    })
+}
+
+/// Every sequence of up to `length` layer turns, shortest first, never
+/// turning a layer twice in a row.
+fn sequences(length: usize) -> Vec<Vec<usize>> {
+    let mut sequences = vec![vec![]];
+    let mut last = vec![vec![]];
+    for _ in 0..length {
+        last = last
+            .iter()
+            .flat_map(|sequence: &Vec<usize>| {
+                (0..LAYERS.len())
+                    .filter(|&index| {
+                        sequence
+                            .last()
+                            .is_none_or(|&last| LAYERS[last].name[..1] != LAYERS[index].name[..1])
+                    })
+                    .map(|index| {
+                        let mut next = sequence.clone();
+                        next.push(index);
+                        next
+                    })
+                    .collect::<Vec<_>>()
+            })
+            .collect();
+        sequences.extend(last.iter().cloned());
+    }
+    sequences
+}
+
+/// A construction without its setup and how many moves it takes.
+struct Entry {
+    construction: Construction,
+    length: usize,
+}
+
+/// The pure commutators and the PLLs for the parity, by what they do.
+static TABLE: LazyLock<HashMap<Vec<Shift>, Entry>> = LazyLock::new(|| {
+    let mut table: HashMap<Vec<Shift>, Entry> = HashMap::new();
+    let mut insert = |effect: Vec<Shift>, entry: Entry| {
+        let known = table.get(&effect);
+        if known.is_none_or(|known| known.length > entry.length) {
+            table.insert(effect, entry);
+        }
+    };
+
+    // 3-cycles of one kind of piece.
+    let inserts = sequences(3);
+    for insert_moves in &inserts {
+        let cube = turn(insert_moves);
+        let inverse: Vec<usize> = insert_moves
+            .iter()
+            .rev()
+            .map(|&index| LAYERS[index].inverse)
+            .collect();
+        let inverse = turn(&inverse);
+        for (index, layer) in LAYERS.iter().enumerate() {
+            let commutator = cube
+                .compose(&layer.cube)
+                .compose(&inverse)
+                .compose(&LAYERS[layer.inverse].cube);
+            let effect = effect(&commutator);
+            let corners = effect.iter().filter(|(from, _)| *from < 24).count();
+            if !matches!((corners, effect.len() - corners), (9, 0) | (0, 6)) {
+                continue;
+            }
+            let length = 2 * (insert_moves.len() + 1);
+            insert(
+                effect,
+                Entry {
+                    construction: Construction::Commutator {
+                        setup: String::new(),
+                        insert: written(insert_moves),
+                        interchange: written(&[index]),
+                    },
+                    length,
+                },
+            );
+        }
+    }
+
+    // Two pieces turning in place.
+    for (algorithm, kind) in [(TWISTER, Kind::Corner), (FLIPPER, Kind::Edge)] {
+        for insert_moves in [algorithm.to_string(), invert(algorithm)] {
+            let insert_moves: Vec<usize> = insert_moves
+                .split_whitespace()
+                .map(|name| LAYERS.iter().position(|layer| layer.name == name).unwrap())
+                .collect();
+            let cube = turn(&insert_moves);
+            let inverse: Vec<usize> = insert_moves
+                .iter()
+                .rev()
+                .map(|&index| LAYERS[index].inverse)
+                .collect();
+            let inverse = turn(&inverse);
+            for interchange in ["U", "U'", "U2"] {
+                let index = LAYERS
+                    .iter()
+                    .position(|layer| layer.name == interchange)
+                    .unwrap();
+                let commutator = cube
+                    .compose(&LAYERS[index].cube)
+                    .compose(&inverse)
+                    .compose(&LAYERS[LAYERS[index].inverse].cube);
+                let effect = effect(&commutator);
+                debug_assert_eq!(effect.len(), 2 * kind.twists());
+                insert(
+                    effect,
+                    Entry {
+                        construction: Construction::Commutator {
+                            setup: String::new(),
+                            insert: written(&insert_moves),
+                            interchange: interchange.to_string(),
+                        },
+                        length: 2 * (insert_moves.len() + 1),
+                    },
+                );
+            }
+        }
+    }
+
+    // Two corners and two edges swapped. Rotations and wide moves would turn
+    // the setup undoing them, so only PLLs in face turns are taken.
+    for (_, algorithm) in PLL {
+        let faces = notation::split(algorithm).iter().all(|r#move| {
+            r#move.starts_with(['U', 'D', 'F', 'B', 'R', 'L']) && !r#move.contains('w')
+        });
+        if !faces {
+            continue;
+        }
+        let Ok(moves) = notation::expand::<SimpleMove>(algorithm) else {
+            continue;
+        };
+        let effect = effect(&Cube::create_solved().apply(&moves));
+        let corners = effect.iter().filter(|(from, _)| *from < 24).count();
+        if (corners, effect.len() - corners) == (6, 4) {
+            insert(
+                effect,
+                Entry {
+                    construction: Construction::Conjugate {
+                        setup: String::new(),
+                        algorithm: algorithm.to_string(),
+                    },
+                    length: notation::split(algorithm).len(),
+                },
+            );
+        }
+    }
+    table
+});
+
+/// Setups of up to three moves with where they take every sticker.
+static SETUPS: LazyLock<Vec<(Vec<usize>, Locations)>> = LazyLock::new(|| {
+    sequences(3)
+        .into_iter()
+        .map(|setup| {
+            let locations = locations(&turn(&setup));
+            (setup, locations)
+        })
+        .collect()
+});
+
+/// The cheapest construction for `effect` with the shortest setup.
+pub(super) fn find(effect: &[Shift]) -> Option<Construction> {
+    let mut best: Option<(usize, usize, &Entry, &[usize])> = None;
+    for (setup, locations) in SETUPS.iter() {
+        if best.is_some_and(|(length, ..)| length < setup.len()) {
+            break;
+        }
+        let Some(entry) = TABLE.get(&conjugated(effect, locations)) else {
+            continue;
+        };
+        let cost = 2 * setup.len() + entry.length;
+        if best.is_none_or(|(_, best, ..)| cost < best) {
+            best = Some((setup.len(), cost, entry, setup));
+        }
+    }
+    let (_, _, entry, setup) = best?;
+    let mut construction = entry.construction.clone();
+    if let Construction::Commutator {
+        setup: written_setup,
+        ..
+    }
+    | Construction::Conjugate {
+        setup: written_setup,
+        ..
+    } = &mut construction
+    {
+        *written_setup = written(setup);
+    }
+    Some(construction)
+}
+
+/// The shortest setup in face turns making `algorithm` do `effect`.
+pub(super) fn setup(effect: &[Shift], algorithm: &str) -> Option<String> {
+    let target = shifts(&algorithm_locations(algorithm)?);
+    SETUPS
+        .iter()
+        .filter(|(setup, _)| setup.iter().all(|&index| index < ALL_MOVES.len()))
+        .find(|(_, locations)| conjugated(effect, locations) == target)
+        .map(|(setup, _)| written(setup))
+}
+
+/// `effect` on the stickers where `locations` took them, which is what `X`
+/// must do for `A X A'` to do `effect`, if `A` moves like `locations`.
+fn conjugated(effect: &[Shift], locations: &Locations) -> Vec<Shift> {
+    let mut moved: Vec<Shift> = effect
+        .iter()
+        .map(|&(from, to)| (locations[from as usize], locations[to as usize]))
+        .collect();
+    moved.sort();
+    moved
+}
+
+/// Where `algorithm`, written in face and slice turns, takes every sticker,
+/// seen from the corners like `SliceMove`.
+pub(super) fn algorithm_locations(algorithm: &str) -> Option<Locations> {
+    let moves = algorithm
+        .split_whitespace()
+        .map(|name| LAYERS.iter().position(|layer| layer.name == name))
+        .collect::<Option<Vec<usize>>>()?;
+    Some(locations(&turn(&moves)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{cube::scrambled, method::bld::Memo};
+
+    #[test]
+    fn writes_commutators() {
+        let commutator = Construction::Commutator {
+            setup: "D".to_string(),
+            insert: "R' D' R".to_string(),
+            interchange: "U2".to_string(),
+        };
+        assert_eq!(commutator.to_string(), "[D: [R' D' R, U2]]");
+        assert_eq!(commutator.notation(), "D R' D' R U2 R' D R U2 D'");
+        let conjugate = Construction::Conjugate {
+            setup: String::new(),
+            algorithm: "R U R'".to_string(),
+        };
+        assert_eq!(conjugate.to_string(), "R U R'");
+    }
+
+    #[test]
+    fn solves_scrambles() {
+        for scramble in [
+            "R U R' U'",
+            "F2 L' U2 B R' D2 F L2 U' R2 B' D L' F2",
+            "D2 F' R2 U L2 B' R D' F2 L U2 B R' F D2 R2 B2 U",
+            "L2 D' B2 U F2 R U' L F' D2 B R2 U2 L' B2",
+        ] {
+            let cube = scrambled(scramble);
+            let execution: Execution<SimpleMove> = solve(&cube, &Scheme::speffz(), 0, 0).unwrap();
+            assert_eq!(
+                cube.apply(&execution.moves()),
+                Cube::create_solved(),
+                "{scramble}\n{execution}"
+            );
+            assert_eq!(execution.solution().moves(), execution.moves());
+        }
+    }
+
+    #[test]
+    fn solves_parity() {
+        let cube = scrambled("R U R' U' R' F R2 U' R' U' R U R' F'");
+        let execution: Execution<SimpleMove> = solve(&cube, &Scheme::speffz(), 0, 0).unwrap();
+        assert!(
+            execution
+                .steps
+                .iter()
+                .any(|step| step.name.starts_with("Parity")),
+            "{execution}"
+        );
+        assert_eq!(cube.apply(&execution.moves()), Cube::create_solved());
+    }
+
+    #[test]
+    fn names_steps_after_targets() {
+        // A U perm cycling three edges from the buffer.
+        let cube = scrambled("R U' R U R U R U' R' U' R2");
+        let memo = Memo::speffz(&cube);
+        let execution: Execution<SimpleMove> = solve(&cube, &Scheme::speffz(), 0, 0).unwrap();
+        let names: Vec<&str> = execution.steps.iter().map(|step| &step.name[..]).collect();
+        let pair: String = memo.edges.iter().collect();
+        assert_eq!(names, [format!("Edges {pair}")]);
+        assert!(execution.steps[0].moves.len() <= 12, "{execution}");
+    }
+}
@@ -0,0 +1,262 @@
+use std::fmt::Display;
+
+use crate::{
+    cube::{Cube, color::Color, mask::Mask, r#move::Move, move_sequence::Sequence},
+    method::{Solution, Stage, last_layer_stage},
+    solver::Search,
+};
+
+/// Orients the last layer, written for a solved bottom layer.
+pub const OLL: [(&str, &str); 57] = [
+    ("1", "R U2 R2 F R F' U2 R' F R F'"),
+    ("2", "F R U R' U' F' f R U R' U' f'"),
+    ("3", "f R U R' U' f' U' F R U R' U' F'"),
+    ("4", "f R U R' U' f' U F R U R' U' F'"),
+    ("5", "r' U2 R U R' U r"),
+    ("6", "r U2 R' U' R U' r'"),
+    ("7", "r U R' U R U2 r'"),
+    ("8", "r' U' R U' R' U2 r"),
+    ("9", "R U R' U' R' F R2 U R' U' F'"),
+    ("10", "R U R' U R' F R F' R U2 R'"),
+    ("11", "r U R' U R' F R F' R U2 r'"),
+    ("12", "M' R' U' R U' R' U2 R U' R r'"),
+    ("13", "F U R U' R2 F' R U R U' R'"),
+    ("14", "R' F R U R' F' R F U' F'"),
+    ("15", "r' U' r R' U' R U r' U r"),
+    ("16", "r U r' R U R' U' r U' r'"),
+    ("17", "R U R' U R' F R F' U2 R' F R F'"),
+    ("18", "r U R' U R U2 r2 U' R U' R' U2 r"),
+    ("19", "r' R U R U R' U' M' R' F R F'"),
+    ("20", "r U R' U' M2 U R U' R' U' M'"),
+    ("21", "R U2 R' U' R U R' U' R U' R'"),
+    ("22", "R U2 R2 U' R2 U' R2 U2 R"),
+    ("23", "R2 D' R U2 R' D R U2 R"),
+    ("24", "r U R' U' r' F R F'"),
+    ("25", "F' r U R' U' r' F R"),
+    ("26", "R U2 R' U' R U' R'"),
+    ("27", "R U R' U R U2 R'"),
+    ("28", "r U R' U' M U R U' R'"),
+    ("29", "R U R' U' R U' R' F' U' F R U R'"),
+    ("30", "F R' F R2 U' R' U' R U R' F2"),
+    ("31", "R' U' F U R U' R' F' R"),
+    ("32", "L U F' U' L' U L F L'"),
+    ("33", "R U R' U' R' F R F'"),
+    ("34", "R U R2 U' R' F R U R U' F'"),
+    ("35", "R U2 R2 F R F' R U2 R'"),
+    ("36", "L' U' L U' L' U L U L F' L' F"),
+    ("37", "F R' F' R U R U' R'"),
+    ("38", "R U R' U R U' R' U' R' F R F'"),
+    ("39", "L F' L' U' L U F U' L'"),
+    ("40", "R' F R U R' U' F' U R"),
+    ("41", "R U R' U R U2 R' F R U R' U' F'"),
+    ("42", "R' U' R U' R' U2 R F R U R' U' F'"),
+    ("43", "F' U' L' U L F"),
+    ("44", "F U R U' R' F'"),
+    ("45", "F R U R' U' F'"),
+    ("46", "R' U' R' F R F' U R"),
+    ("47", "R' U' R' F R F' R' F R F' U R"),
+    ("48", "F R U R' U' R U R' U' F'"),
+    ("49", "r U' r2 U r2 U r2 U' r"),
+    ("50", "r' U r2 U' r2 U' r2 U r'"),
+    ("51", "F U R U' R' U R U' R' F'"),
+    ("52", "R U R' U R U' B U' B' R'"),
+    ("53", "r' U' R U' R' U R U' R' U2 r"),
+    ("54", "r U R' U R U' R' U R U2 r'"),
+    ("55", "R' F R U R U' R2 F' R2 U' R' U R U R'"),
+    ("56", "r' U' r U' R' U R U' R' U R r' U r"),
+    ("57", "R U R' U' M' U R U' r'"),
+];
+
+/// Permutes the last layer once it's oriented.
+pub const PLL: [(&str, &str); 21] = [
+    ("Aa", "x R' U R' D2 R U' R' D2 R2 x'"),
+    ("Ab", "x R2 D2 R U R' D2 R U' R x'"),
+    ("E", "x' R U' R' D R U R' D' R U R' D R U' R' D' x"),
+    ("F", "R' U' F' R U R' U' R' F R2 U' R' U' R U R' U R"),
+    ("Ga", "R2 U R' U R' U' R U' R2 U' D R' U R D'"),
+    ("Gb", "R' U' R U D' R2 U R' U R U' R U' R2 D"),
+    ("Gc", "R2 U' R U' R U R' U R2 U D' R U' R' D"),
+    ("Gd", "R U R' U' D R2 U' R U' R' U R' U R2 D'"),
+    ("H", "M2 U M2 U2 M2 U M2"),
+    ("Ja", "x R2 F R F' R U2 r' U r U2 x'"),
+    ("Jb", "R U R' F' R U R' U' R' F R2 U' R'"),
+    (
+        "Na",
+        "R U R' U R U R' F' R U R' U' R' F R2 U' R' U2 R U' R'",
+    ),
+    ("Nb", "R' U R U' R' F' U' F R U R' F R' F' R U' R"),
+    ("Ra", "R U' R' U' R U R D R' U' R D' R' U2 R'"),
+    ("Rb", "R2 F R U R U' R' F' R U2 R' U2 R"),
+    ("T", "R U R' U' R' F R2 U' R' U' R U R' F'"),
+    ("Ua", "M2 U M U2 M' U M2"),
+    ("Ub", "M2 U' M U2 M' U' M2"),
+    ("V", "R' U R' U' y R' F' R2 U' R' U R' F R F"),
+    ("Y", "F R U' R' U' R U R' F' R U R' U' R' F R F'"),
+    ("Z", "M' U M2 U M2 U M' U2 M2"),
+];
+
+/// Solves `cube` the way a CFOP solver would: an optimal cross of `color`,
+/// the four F2L pairs one at a time, always taking the one that's quickest to
+/// insert next, then OLL and PLL from the built-in algorithms.
+pub fn solve<T: Move + Display>(cube: &Cube, color: Color) -> Option<Solution<T>> {
+    let side = color.side();
+    let mut cube = cube.clone();
+    let mut stages = Vec::new();
+
+    let mut solved = Mask::cross_on(side);
+    let cross: Sequence<T> = Search::new().goal(solved.clone()).solve(cube.clone())?;
+    cube = cube.apply(&cross);
+    stages.push(Stage {
+        name: "Cross".to_string(),
+        notation: cross.to_string(),
+        moves: cross,
+    });
+
+    let mut slots = Mask::slots(side);
+    for pair in 1..=4 {
+        let (index, moves) = slots
+            .iter()
+            .enumerate()
+            .filter_map(|(index, &slot)| {
+                let goal = solved.union(&Mask::pair(side, slot));
+                let moves: Sequence<T> = Search::new().goal(goal).solve(cube.clone())?;
+                Some((index, moves))
+            })
+            .min_by_key(|(_, moves)| moves.len())?;
+        solved = solved.union(&Mask::pair(side, slots.remove(index)));
+        cube = cube.apply(&moves);
+        stages.push(Stage {
+            name: format!("F2L {pair}"),
+            notation: moves.to_string(),
+            moves,
+        });
+    }
+
+    let last_layer = side.opposite() as usize;
+    let oriented = |cube: &Cube| {
+        let face = cube.faces()[last_layer];
+        face.iter().all(|color| *color == face[4])
+    };
+    let case = last_layer_stage(&cube, side, &OLL, oriented, false)?;
+    cube = cube.apply(&case.moves);
+    stages.push(Stage {
+        name: format!("OLL {}", case.name),
+        notation: case.algorithm,
+        moves: case.moves,
+    });
+
+    let case = last_layer_stage(&cube, side, &PLL, |cube| Mask::all().matches(cube), true)?;
+    stages.push(Stage {
+        name: format!("PLL {}", case.name),
+        notation: case.algorithm,
+        moves: case.moves,
+    });
+    Some(Solution { stages })
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashSet;
+
+    use super::*;
+    use crate::cube::{
+        notation,
+        simple_move::{Side, SimpleMove, Turn},
+    };
+
+    /// The case an algorithm solves, the same for every turn of the last layer
+    /// before and after it.
+    fn case(algorithm: &str, orientation_only: bool) -> (Vec<u8>, Cube) {
+        let algorithm: Sequence<SimpleMove> = notation::expand(algorithm).unwrap();
+        let cube = Cube::create_solved().apply(&algorithm.invert());
+        let up = |turns| SimpleMove::from_parts(Side::UP, turns);
+        let turns = [
+            None,
+            Some(up(Turn::RIGHT)),
+            Some(up(Turn::TWO)),
+            Some(up(Turn::LEFT)),
+        ];
+        let key = turns
+            .iter()
+            .flat_map(|before| turns.iter().map(move |after| (before, after)))
+            .map(|(before, after)| {
+                let mut turned = cube.clone();
+                for r#move in before
+                    .iter()
+                    .chain(after.iter().filter(|_| !orientation_only))
+                {
+                    turned = turned.apply_move(r#move);
+                }
+                let mut key: Vec<u8> = turned.corner_orientation().to_vec();
+                key.extend(turned.edge_orientation());
+                if !orientation_only {
+                    key.extend(turned.corner_permutation());
+                    key.extend(turned.edge_permutation());
+                }
+                key
+            })
+            .min()
+            .unwrap();
+        (key, cube)
+    }
+
+    #[test]
+    fn oll_algorithms_cover_every_case() {
+        let mut cases = HashSet::new();
+        for (name, algorithm) in OLL {
+            let (key, cube) = case(algorithm, true);
+            assert!(
+                Mask::first_two_layers_on(Side::DOWN).matches(&cube),
+                "OLL {name}"
+            );
+            assert!(cases.insert(key), "OLL {name}");
+        }
+        assert!(!cases.contains(&vec![0; 20]));
+    }
+
+    #[test]
+    fn pll_algorithms_cover_every_case() {
+        let mut cases = HashSet::new();
+        for (name, algorithm) in PLL {
+            let (key, cube) = case(algorithm, false);
+            assert!(
+                Mask::first_two_layers_on(Side::DOWN).matches(&cube),
+                "PLL {name}"
+            );
+            assert_eq!(&key[..20], &[0; 20], "PLL {name}");
+            assert!(cases.insert(key), "PLL {name}");
+        }
+        assert!(!cases.contains(&case("", false).0));
+    }
+
+    #[test]
+    fn solves_in_stages() {
+        let scramble: Sequence<SimpleMove> = "D2 F' R2 U L2 B' R D' F2 L U2 B R' F D2 R2 B2 U"
+            .parse()
+            .unwrap();
+        let cube = Cube::create_solved().apply(&scramble);
+        for color in [Color::YELLOW, Color::WHITE, Color::GREEN] {
+            let solution: Solution<SimpleMove> = solve(&cube, color).unwrap();
+            let names: Vec<&str> = solution
+                .stages
+                .iter()
+                .map(|stage| &stage.name[..3])
+                .collect();
+            assert_eq!(names, ["Cro", "F2L", "F2L", "F2L", "F2L", "OLL", "PLL"]);
+            for stage in &solution.stages[5..] {
+                let written: Sequence<SimpleMove> = notation::expand(&stage.notation).unwrap();
+                assert_eq!(written, stage.moves, "{}", stage.name);
+                // The last layer is on the side, so the algorithm is written
+                // after a rotation.
+                if color == Color::GREEN && !stage.moves.is_empty() {
+                    assert!(stage.notation.starts_with('x'), "{}", stage.notation);
+                }
+            }
+            assert_eq!(
+                cube.apply(&solution.moves()),
+                Cube::create_solved(),
+                "{solution}"
+            );
+        }
+    }
+}
@@ -0,0 +1,858 @@
+use std::{
+    collections::{HashMap, HashSet},
+    fmt::Display,
+    hash::Hash,
+    sync::{Arc, LazyLock, Mutex},
+};
+
+use crate::{
+    cube::{
+        Cube, EDGE_SIDES,
+        r#move::Move,
+        move_sequence::Sequence,
+        simple_move::{ALL_MOVES, Side, SimpleMove},
+    },
+    solver::{Search, thistlethwaite::Phase},
+};
+
+/// One side of each axis, standing for the axis.
+const AXES: [Side; 3] = [Side::UP, Side::FRONT, Side::RIGHT];
+
+/// Every move, as indices into `Move::all_moves`.
+const EVERY_MOVE: &[usize] = &[0, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15, 16, 17];
+
+/// The moves keeping the edges oriented on each axis of `AXES`.
+const KEEPING_EDGES: [&[usize]; 3] = [
+    // <U2, D2, F, B, R, L>
+    &[2, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15, 16, 17],
+    // <U, D, F2, B2, R, L>
+    &[0, 1, 2, 3, 4, 5, 8, 11, 12, 13, 14, 15, 16, 17],
+    // <U, D, F, B, R2, L2>
+    &[0, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 14, 17],
+];
+
+/// The moves keeping a domino on each axis of `AXES`.
+const DOMINO_MOVES: [&[usize]; 3] = [
+    // <U, D, F2, B2, R2, L2>
+    &[0, 1, 2, 3, 4, 5, 8, 11, 14, 17],
+    // <U2, D2, F, B, R2, L2>
+    &[2, 5, 6, 7, 8, 9, 10, 11, 14, 17],
+    // <U2, D2, F2, B2, R, L>
+    &[2, 5, 8, 11, 12, 13, 14, 15, 16, 17],
+];
+
+const EDGE_ORIENTATIONS: [fn(&Cube) -> u64; 3] = [
+    edge_orientation::<0>,
+    edge_orientation::<1>,
+    edge_orientation::<2>,
+];
+
+const CORNER_ORIENTATIONS: [fn(&Cube) -> u64; 3] = [
+    corner_orientation::<0>,
+    corner_orientation::<1>,
+    corner_orientation::<2>,
+];
+
+const SLICE_EDGES: [fn(&Cube) -> u64; 3] = [slice_edges::<0>, slice_edges::<1>, slice_edges::<2>];
+
+const LAYER_EDGES: [fn(&Cube) -> u64; 3] = [layer_edges::<0>, layer_edges::<1>, layer_edges::<2>];
+
+static PHASES: LazyLock<Mutex<HashMap<Goal, Arc<Vec<Phase>>>>> =
+    LazyLock::new(|| Mutex::new(HashMap::new()));
+
+/// The side of the scramble moves are found on.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Direction {
+    Normal,
+    Inverse,
+}
+
+/// A fewest-moves attempt switching between the scramble and its inverse
+/// (NISS). Moves found on the inverse scramble go at the end of the
+/// solution, undone in reverse order.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Niss<T: Move> {
+    pub scramble: Sequence<T>,
+    pub normal: Sequence<T>,
+    pub inverse: Sequence<T>,
+}
+
+impl<T: Move> Niss<T> {
+    pub fn new(scramble: Sequence<T>) -> Self {
+        Niss {
+            scramble,
+            normal: Sequence::new(),
+            inverse: Sequence::new(),
+        }
+    }
+
+    /// The cube as seen from `direction`: its scramble, with the moves found
+    /// on the other side undone before it as premoves, then its own moves.
+    pub fn cube(&self, direction: Direction) -> Cube {
+        let solved = Cube::create_solved();
+        match direction {
+            Direction::Normal => solved
+                .apply(&self.inverse.invert())
+                .apply(&self.scramble)
+                .apply(&self.normal),
+            Direction::Inverse => solved
+                .apply(&self.normal.invert())
+                .apply(&self.scramble.invert())
+                .apply(&self.inverse),
+        }
+    }
+
+    /// Adds `moves` to the moves of `direction`.
+    pub fn push(&mut self, direction: Direction, moves: &Sequence<T>) {
+        let side = match direction {
+            Direction::Normal => &mut self.normal,
+            Direction::Inverse => &mut self.inverse,
+        };
+        *side = side.concat(moves);
+    }
+
+    /// Reaches the goal of `search` on whichever side takes fewer moves in
+    /// its metric, the normal one on a tie, and adds the moves there.
+    pub fn step(&mut self, search: &Search<T>) -> Option<(Direction, Sequence<T>)> {
+        let (direction, moves) = [Direction::Normal, Direction::Inverse]
+            .into_iter()
+            .filter_map(|direction| Some((direction, search.solve(self.cube(direction))?)))
+            .min_by_key(|(_, moves)| search.cost(moves))?;
+        self.push(direction, &moves);
+        Some((direction, moves))
+    }
+
+    /// The normal moves followed by the inverse ones undone, with the moves
+    /// where they meet cancelled.
+    pub fn solution(&self) -> Sequence<T> {
+        self.normal.concat(&self.inverse.invert()).simplify()
+    }
+
+    pub fn is_solved(&self) -> bool {
+        self.cube(Direction::Normal) == Cube::create_solved()
+    }
+}
+
+/// Written like fewest-moves solvers do, with the inverse moves in
+/// parentheses.
+impl<T: Move + Display> Display for Niss<T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match (self.normal.is_empty(), self.inverse.is_empty()) {
+            (_, true) => write!(f, "{}", self.normal),
+            (true, false) => write!(f, "({})", self.inverse),
+            (false, false) => write!(f, "{} ({})", self.normal, self.inverse),
+        }
+    }
+}
+
+/// Moves reaching a step of a fewest-moves solution on an axis, named by
+/// its side among `U`, `F` and `R`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Candidate<T: Move> {
+    pub axis: Side,
+    pub moves: Sequence<T>,
+}
+
+/// Every way of orienting the edges of `cube` on each axis, up to `extra`
+/// moves longer than the shortest one on its axis, fewest moves first.
+pub fn edge_orientations<T: Move>(cube: &Cube, extra: usize) -> Vec<Candidate<T>> {
+    let mut candidates: Vec<Candidate<T>> = AXES
+        .into_iter()
+        .flat_map(|axis| {
+            sequences(cube, Goal::EdgeOrientation(axis), extra)
+                .into_iter()
+                .map(move |moves| Candidate { axis, moves })
+        })
+        .collect();
+    candidates.sort_by_key(|candidate| candidate.moves.len());
+    candidates
+}
+
+/// Every way of reducing `cube`, with its edges oriented on the axis of
+/// `oriented`, to a domino on one of the other two axes (DR), up to `extra`
+/// moves longer than the shortest one on its axis, fewest moves first.
+///
+/// A domino has its corners oriented on its axis and its edges on both
+/// others, so quarter turns of its axis and half turns of the others solve
+/// it. The moves keep the edges oriented, turning the sides of `oriented`
+/// only by half turns. Empty if the edges aren't oriented.
+pub fn domino_reductions<T: Move>(cube: &Cube, oriented: Side, extra: usize) -> Vec<Candidate<T>> {
+    let oriented = AXES[oriented.axis() as usize];
+    if cube.edge_orientation_along(oriented) != [0; 12] {
+        return Vec::new();
+    }
+    let mut candidates: Vec<Candidate<T>> = AXES
+        .into_iter()
+        .filter(|axis| *axis != oriented)
+        .flat_map(|axis| {
+            sequences(cube, Goal::Domino { oriented, axis }, extra)
+                .into_iter()
+                .map(move |moves| Candidate { axis, moves })
+        })
+        .collect();
+    candidates.sort_by_key(|candidate| candidate.moves.len());
+    candidates
+}
+
+/// Every way of solving `cube`, reduced to a domino on the axis of `axis`,
+/// with quarter turns of that axis and half turns of the others, like
+/// `<U,D,R2,L2,F2,B2>` along `U`, up to `extra` moves longer than the
+/// shortest one, fewest moves first. Empty if it isn't a domino.
+pub fn domino_finishes<T: Move>(cube: &Cube, axis: Side, extra: usize) -> Vec<Sequence<T>> {
+    let axis = AXES[axis.axis() as usize];
+    let domino = cube.corner_orientation_along(axis) == [0; 8]
+        && AXES
+            .into_iter()
+            .filter(|other| *other != axis)
+            .all(|other| cube.edge_orientation_along(other) == [0; 12]);
+    if !domino || !cube.is_solvable() {
+        return Vec::new();
+    }
+    sequences(cube, Goal::Solved(axis), extra)
+}
+
+/// A partial state that a step of a fewest-moves solution reaches.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+enum Goal {
+    /// The edges oriented on the axis of a side.
+    EdgeOrientation(Side),
+    /// A domino on `axis`, keeping the edges oriented on `oriented`.
+    Domino { oriented: Side, axis: Side },
+    /// The solved cube, from a domino on the axis of a side.
+    Solved(Side),
+}
+
+impl Goal {
+    /// The distance to the goal from every state of the pieces each phase
+    /// looks at. The goal is reached once every phase is at distance 0.
+    fn phases(self) -> Vec<Phase> {
+        let reached = |keys: &[u64]| keys.iter().all(|&key| key == 0);
+        match self {
+            Goal::EdgeOrientation(axis) => vec![Phase::new(
+                &format!("fmc-eo-{}", axis.axis()),
+                EVERY_MOVE,
+                &[EDGE_ORIENTATIONS[axis.axis() as usize]],
+                reached,
+            )],
+            Goal::Domino { oriented, axis } => {
+                // The edges stay oriented on `oriented`, so only the third
+                // axis is left to orient them on.
+                let other = 3 - oriented.axis() - axis.axis();
+                vec![Phase::new(
+                    &format!("fmc-domino-{}-{}", oriented.axis(), axis.axis()),
+                    KEEPING_EDGES[oriented.axis() as usize],
+                    &[
+                        CORNER_ORIENTATIONS[axis.axis() as usize],
+                        EDGE_ORIENTATIONS[other as usize],
+                    ],
+                    reached,
+                )]
+            }
+            Goal::Solved(axis) => {
+                // The slice edges only move within their slice, so both
+                // phases are small enough to hold every state.
+                let axis = axis.axis() as usize;
+                let solved = Cube::create_solved();
+                let is_solved = |encoders: [fn(&Cube) -> u64; 2]| {
+                    let solved = encoders.map(|encode| encode(&solved));
+                    move |keys: &[u64]| keys == solved
+                };
+                let corners = [corners as fn(&Cube) -> u64, SLICE_EDGES[axis]];
+                let edges = [LAYER_EDGES[axis], SLICE_EDGES[axis]];
+                vec![
+                    Phase::new(
+                        &format!("fmc-corners-{axis}"),
+                        DOMINO_MOVES[axis],
+                        &corners,
+                        is_solved(corners),
+                    ),
+                    Phase::new(
+                        &format!("fmc-edges-{axis}"),
+                        DOMINO_MOVES[axis],
+                        &edges,
+                        is_solved(edges),
+                    ),
+                ]
+            }
+        }
+    }
+}
+
+/// Every sequence reaching `goal` from `cube` up to `extra` moves longer than
+/// the shortest one, without reaching it on the way.
+fn sequences<T: Move>(cube: &Cube, goal: Goal, extra: usize) -> Vec<Sequence<T>> {
+    let phases = PHASES
+        .lock()
+        .unwrap()
+        .entry(goal)
+        .or_insert_with(|| Arc::new(goal.phases()))
+        .clone();
+    let Some(indices) = phases
+        .iter()
+        .map(|phase| phase.index(cube))
+        .collect::<Option<Vec<usize>>>()
+    else {
+        return Vec::new();
+    };
+    let Some(mut length) = estimate(&phases, &indices) else {
+        return Vec::new();
+    };
+    let moves: Vec<T> = phases[0]
+        .moves
+        .iter()
+        .map(|&r#move| T::all_moves()[r#move].clone())
+        .collect();
+    let mut found = Vec::new();
+    while found.is_empty() {
+        search(
+            &phases,
+            &moves,
+            &indices,
+            length,
+            &mut Vec::new(),
+            &mut found,
+        );
+        length += 1;
+    }
+    for length in length..length + extra {
+        search(
+            &phases,
+            &moves,
+            &indices,
+            length,
+            &mut Vec::new(),
+            &mut found,
+        );
+    }
+    found
+}
+
+/// The most moves any of `phases` needs from `indices`, `None` if one of
+/// them can't be reached.
+fn estimate(phases: &[Phase], indices: &[usize]) -> Option<usize> {
+    phases
+        .iter()
+        .zip(indices)
+        .map(|(phase, &index)| match phase.distances[index] {
+            u8::MAX => None,
+            distance => Some(distance as usize),
+        })
+        .try_fold(0, |estimate, distance| Some(estimate.max(distance?)))
+}
+
+/// Collects the sequences reaching the goal of `phases` in exactly `left`
+/// more of their `moves`. Opposite sides are only turned in one order, like
+/// `Search` does.
+fn search<T: Move>(
+    phases: &[Phase],
+    moves: &[T],
+    indices: &[usize],
+    left: usize,
+    path: &mut Vec<T>,
+    found: &mut Vec<Sequence<T>>,
+) {
+    let Some(estimate) = estimate(phases, indices) else {
+        return;
+    };
+    let reached = phases
+        .iter()
+        .zip(indices)
+        .all(|(phase, &index)| phase.distances[index] == 0);
+    if left == 0 {
+        if reached {
+            found.push(path.iter().cloned().collect());
+        }
+        return;
+    }
+    if reached || estimate > left {
+        return;
+    }
+    for (position, r#move) in moves.iter().enumerate() {
+        if path.last().is_some_and(|previous| {
+            previous.side() == r#move.side()
+                || previous.side().axis() == r#move.side().axis()
+                    && (previous.side() as u8) > (r#move.side() as u8)
+        }) {
+            continue;
+        }
+        let next: Vec<usize> = phases
+            .iter()
+            .zip(indices)
+            .map(|(phase, &index)| phase.next(index, position))
+            .collect();
+        path.push(r#move.clone());
+        search(phases, moves, &next, left - 1, path, found);
+        path.pop();
+    }
+}
+
+fn edge_orientation<const AXIS: usize>(cube: &Cube) -> u64 {
+    pack(&cube.edge_orientation_along(AXES[AXIS]), 1)
+}
+
+fn corner_orientation<const AXIS: usize>(cube: &Cube) -> u64 {
+    pack(&cube.corner_orientation_along(AXES[AXIS]), 2)
+}
+
+fn corners(cube: &Cube) -> u64 {
+    pack(cube.corner_permutation(), 3)
+}
+
+/// Where the edges between the sides of the axis are, which a domino keeps
+/// between them.
+fn slice_edges<const AXIS: usize>(cube: &Cube) -> u64 {
+    edges_in_slice(cube, AXIS, true)
+}
+
+fn layer_edges<const AXIS: usize>(cube: &Cube) -> u64 {
+    edges_in_slice(cube, AXIS, false)
+}
+
+fn edges_in_slice(cube: &Cube, axis: usize, slice: bool) -> u64 {
+    let pieces: Vec<u8> = (0..12)
+        .filter(|&slot| {
+            EDGE_SIDES[slot]
+                .iter()
+                .all(|side| side.axis() as usize != axis)
+                == slice
+        })
+        .map(|slot| cube.edge_permutation()[slot])
+        .collect();
+    pack(&pieces, 4)
+}
+
+fn pack(values: &[u8], bits: u32) -> u64 {
+    values
+        .iter()
+        .fold(0, |packed, &value| packed << bits | value as u64)
+}
+
+/// An algorithm inserted into a skeleton before the move at `position`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Insertion<T: Move> {
+    pub position: usize,
+    pub algorithm: Sequence<T>,
+}
+
+/// The insertions finishing a skeleton, each made in the skeleton with the
+/// ones before it, and the solution they lead to.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Insertions<T: Move> {
+    pub insertions: Vec<Insertion<T>>,
+    pub solution: Sequence<T>,
+}
+
+/// Finds every way of finishing `skeleton` on `scramble` with insertions
+/// of pure commutators, fewest moves first after cancellations.
+///
+/// Each insertion is a pure 3-cycle of corners or edges: a commutator
+/// `[B, C]` of up to three moves in `B`, or four for edges, and one in `C`,
+/// also behind a setup move, at any position of the skeleton. A 3-cycle the
+/// skeleton leaves takes one of them, and two swapped pairs, two twisted
+/// corners, two flipped edges or a longer cycle take two. Empty if the
+/// skeleton leaves anything more, like three twisted corners or an odd
+/// permutation, or if no insertion makes the cycles it needs.
+pub fn insertions<T: Move + Eq + Hash>(
+    scramble: &Sequence<T>,
+    skeleton: &Sequence<T>,
+) -> Vec<Insertions<T>> {
+    let mut found = Vec::new();
+    finish(scramble, skeleton.clone(), Vec::new(), &mut found);
+    found.sort_by_key(|insertions| insertions.solution.len());
+    let mut seen = HashSet::new();
+    found.retain(|insertions| seen.insert(insertions.solution.clone()));
+    found
+}
+
+fn finish<T: Move>(
+    scramble: &Sequence<T>,
+    skeleton: Sequence<T>,
+    done: Vec<Insertion<T>>,
+    found: &mut Vec<Insertions<T>>,
+) {
+    let solved = Cube::create_solved();
+    let residue = solved.apply(scramble).apply(&skeleton);
+    if residue == solved {
+        found.push(Insertions {
+            insertions: done,
+            solution: skeleton,
+        });
+        return;
+    }
+    let moves = skeleton.moves();
+    for undo in undos(&residue) {
+        for position in 0..=moves.len() {
+            let before: Sequence<T> = moves[..position].iter().cloned().collect();
+            let after: Sequence<T> = moves[position..].iter().cloned().collect();
+            // The cycle is undone at the end of the skeleton, so it has to be
+            // undone as the moves after the insertion will have moved it.
+            let moved = solved.apply(&after);
+            let needed = moved.compose(&undo).compose(&moved.inverse());
+            let Some(algorithms) = COMMUTATORS.get(&needed) else {
+                continue;
+            };
+            for algorithm in algorithms {
+                let algorithm: Sequence<T> = algorithm
+                    .iter()
+                    .map(|r#move| T::from_parts(r#move.side, r#move.turns))
+                    .collect();
+                let solution = before.concat(&algorithm).concat(&after).simplify();
+                let mut done = done.clone();
+                done.push(Insertion {
+                    position,
+                    algorithm,
+                });
+                finish(scramble, solution, done, found);
+            }
+        }
+    }
+}
+
+/// The 3-cycles to undo next at the end of the skeleton: the first one the
+/// corners, then the edges, are left with, turning no piece in total. When
+/// the pieces of the kind have none, every 3-cycle that leaves them one.
+fn undos(residue: &Cube) -> Vec<Cube> {
+    let kinds = [
+        (
+            cycles(residue.corner_permutation(), residue.corner_orientation()),
+            3,
+        ),
+        (
+            cycles(residue.edge_permutation(), residue.edge_orientation()),
+            2,
+        ),
+    ];
+    for (kind, (cycles, twists)) in kinds.into_iter().enumerate() {
+        let only = |slots: &[usize]| match kind {
+            0 => residue.only(slots, &[]),
+            _ => residue.only(&[], slots),
+        };
+        if let Some((slots, _)) = cycles
+            .iter()
+            .find(|(slots, twist)| slots.len() == 3 && twist.is_multiple_of(twists))
+        {
+            return vec![only(slots).inverse()];
+        }
+        if !cycles.is_empty() {
+            let slots: Vec<usize> = cycles.into_iter().flat_map(|(slots, _)| slots).collect();
+            let pieces = only(&slots);
+            return CYCLES
+                .iter()
+                .filter(|cycle| is_cycle(&pieces.compose(cycle)))
+                .cloned()
+                .collect();
+        }
+    }
+    Vec::new()
+}
+
+/// The unsolved cycles of one kind of piece, each with how much its pieces
+/// are turned in total.
+fn cycles(permutation: &[u8], orientation: &[u8]) -> Vec<(Vec<usize>, u8)> {
+    let mut cycles = Vec::new();
+    let mut seen = vec![false; permutation.len()];
+    for start in 0..permutation.len() {
+        if seen[start] || (permutation[start] as usize == start && orientation[start] == 0) {
+            continue;
+        }
+        let mut slots = vec![start];
+        seen[start] = true;
+        while permutation[slots[slots.len() - 1]] as usize != start {
+            let next = permutation[slots[slots.len() - 1]] as usize;
+            seen[next] = true;
+            slots.push(next);
+        }
+        let twist = slots.iter().map(|&slot| orientation[slot]).sum();
+        cycles.push((slots, twist));
+    }
+    cycles
+}
+
+/// Whether the cube is a single 3-cycle that turns no piece in total.
+fn is_cycle(cube: &Cube) -> bool {
+    let corners = cycles(cube.corner_permutation(), cube.corner_orientation());
+    let edges = cycles(cube.edge_permutation(), cube.edge_orientation());
+    match (&corners[..], &edges[..]) {
+        ([(slots, twist)], []) => slots.len() == 3 && twist.is_multiple_of(3),
+        ([], [(slots, flip)]) => slots.len() == 3 && flip.is_multiple_of(2),
+        _ => false,
+    }
+}
+
+/// The pure 3-cycles of face turns, by what they do.
+static COMMUTATORS: LazyLock<HashMap<Cube, Vec<Vec<SimpleMove>>>> = LazyLock::new(|| {
+    let solved = Cube::create_solved();
+    let pure = |cube: &Cube| {
+        let corners = (0..8)
+            .filter(|&slot| {
+                cube.corner_permutation()[slot] as usize != slot
+                    || cube.corner_orientation()[slot] != 0
+            })
+            .count();
+        let edges = (0..12)
+            .filter(|&slot| {
+                cube.edge_permutation()[slot] as usize != slot || cube.edge_orientation()[slot] != 0
+            })
+            .count();
+        matches!((corners, edges), (3, 0) | (0, 3))
+    };
+
+    let mut inserts: Vec<Sequence<SimpleMove>> = vec![Sequence::new()];
+    let mut last = inserts.clone();
+    for _ in 0..4 {
+        last = last
+            .iter()
+            .flat_map(|insert| {
+                ALL_MOVES
+                    .iter()
+                    .filter(|r#move| {
+                        insert
+                            .moves()
+                            .last()
+                            .is_none_or(|last| last.side != r#move.side)
+                    })
+                    .map(|r#move| insert.apply(r#move))
+                    .collect::<Vec<_>>()
+            })
+            .collect();
+        inserts.extend(last.iter().cloned());
+    }
+
+    let mut commutators: Vec<Sequence<SimpleMove>> = Vec::new();
+    for insert in &inserts[1..] {
+        for interchange in &ALL_MOVES {
+            let interchange: Sequence<SimpleMove> = [interchange.clone()].into_iter().collect();
+            let commutator = insert
+                .concat(&interchange)
+                .concat(&insert.invert())
+                .concat(&interchange.invert());
+            let cube = solved.apply(&commutator);
+            // Only edges need four moves in `B`: shorter commutators never
+            // flip them.
+            let corners = cube.only(&[0, 1, 2, 3, 4, 5, 6, 7], &[]);
+            if pure(&cube) && (insert.len() < 4 || corners == solved) {
+                commutators.push(commutator.simplify());
+            }
+        }
+    }
+    let mut table: HashMap<Cube, Vec<Vec<SimpleMove>>> = HashMap::new();
+    for commutator in &commutators {
+        let setups = std::iter::once(Sequence::new())
+            .chain(ALL_MOVES.iter().map(|r#move| Sequence::new().apply(r#move)));
+        for setup in setups {
+            let algorithm = setup.concat(commutator).concat(&setup.invert()).simplify();
+            let algorithms = table.entry(solved.apply(&algorithm)).or_default();
+            if !algorithms.iter().any(|known| known == algorithm.moves()) {
+                algorithms.push(algorithm.moves().to_vec());
+            }
+        }
+    }
+    table
+});
+
+/// Every pure 3-cycle of corners or of edges, found by moving the ones the
+/// commutators make.
+static CYCLES: LazyLock<Vec<Cube>> = LazyLock::new(|| {
+    let turns: Vec<Cube> = ALL_MOVES
+        .iter()
+        .map(|r#move| Cube::create_solved().apply_move(r#move))
+        .collect();
+    let mut cycles: HashSet<Cube> = COMMUTATORS.keys().cloned().collect();
+    let mut last: Vec<Cube> = cycles.iter().cloned().collect();
+    while !last.is_empty() {
+        last = last
+            .iter()
+            .flat_map(|cycle| {
+                turns
+                    .iter()
+                    .map(move |turn| turn.inverse().compose(cycle).compose(turn))
+            })
+            .filter(|cycle| cycles.insert(cycle.clone()))
+            .collect();
+    }
+    cycles.into_iter().collect()
+});
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cube::{mask::Mask, simple_move::Turn};
+
+    fn sequence(moves: &str) -> Sequence<SimpleMove> {
+        moves.parse().unwrap()
+    }
+
+    #[test]
+    fn merges_normal_and_inverse_moves() {
+        let mut niss = Niss::new(sequence("R U F"));
+        niss.push(Direction::Normal, &sequence("F'"));
+        assert!(!niss.is_solved());
+        niss.push(Direction::Inverse, &sequence("R U"));
+        assert!(niss.is_solved());
+        assert_eq!(niss.to_string(), "F' (R U)");
+        assert_eq!(niss.solution().to_string(), "F' U' R'");
+        assert_eq!(niss.cube(Direction::Inverse), Cube::create_solved());
+    }
+
+    #[test]
+    fn steps_on_the_shorter_side() {
+        let scramble = sequence("R U F");
+        let mut niss = Niss::new(scramble.clone());
+        let search = Search::new().goal(Mask::edge_orientation());
+        let (direction, moves) = niss.step(&search).unwrap();
+        assert_eq!(direction, Direction::Normal);
+        assert_eq!(moves.len(), 1);
+        assert!(Mask::edge_orientation().matches(&niss.cube(Direction::Normal)));
+
+        // Either way of turning `F` orients the edges, and the cube is three
+        // moves from solved after both.
+        let (_, rest) = niss.step(&Search::new()).unwrap();
+        assert!(rest.len() <= 3, "{rest}");
+        assert!(niss.is_solved());
+        let solution = niss.solution();
+        assert_eq!(
+            Cube::create_solved().apply(&scramble).apply(&solution),
+            Cube::create_solved()
+        );
+    }
+
+    #[test]
+    fn lists_edge_orientations_on_every_axis() {
+        let cube = Cube::create_solved().apply(&sequence("R U F"));
+        let candidates: Vec<Candidate<SimpleMove>> = edge_orientations(&cube, 1);
+        for axis in AXES {
+            assert!(candidates.iter().any(|candidate| candidate.axis == axis));
+        }
+        assert_eq!(candidates[0].axis, Side::FRONT);
+        assert_eq!(candidates[0].moves.len(), 1);
+        assert!(
+            candidates
+                .windows(2)
+                .all(|pair| pair[0].moves.len() <= pair[1].moves.len())
+        );
+        for candidate in &candidates {
+            let oriented = cube.apply(&candidate.moves);
+            assert_eq!(oriented.edge_orientation_along(candidate.axis), [0; 12]);
+        }
+    }
+
+    #[test]
+    fn reduces_oriented_edges_to_a_domino() {
+        let cube = Cube::create_solved().apply(&sequence("R U L' D R2 U' F2 L"));
+        let candidates: Vec<Candidate<SimpleMove>> = domino_reductions(&cube, Side::BACK, 0);
+        assert!(!candidates.is_empty());
+        for candidate in &candidates {
+            assert_ne!(candidate.axis, Side::FRONT);
+            let reduced = cube.apply(&candidate.moves);
+            assert_eq!(reduced.corner_orientation_along(candidate.axis), [0; 8]);
+            for axis in AXES.into_iter().filter(|axis| *axis != candidate.axis) {
+                assert_eq!(reduced.edge_orientation_along(axis), [0; 12]);
+            }
+            assert!(candidate.moves.moves().iter().all(|r#move| {
+                r#move.side.axis() != Side::FRONT.axis() || r#move.turns == Turn::TWO
+            }));
+        }
+
+        let unoriented = Cube::create_solved().apply(&sequence("F"));
+        assert!(domino_reductions::<SimpleMove>(&unoriented, Side::FRONT, 2).is_empty());
+    }
+
+    #[test]
+    fn finishes_a_domino_with_its_moves() {
+        let cube = Cube::create_solved().apply(&sequence("U R2 D' F2 U2 L2"));
+        let finishes: Vec<Sequence<SimpleMove>> = domino_finishes(&cube, Side::DOWN, 1);
+        assert_eq!(finishes[0].len(), 6);
+        assert!(finishes.len() > 1);
+        for finish in &finishes {
+            assert_eq!(cube.apply(finish), Cube::create_solved());
+            assert!(finish.moves().iter().all(|r#move| {
+                r#move.side.axis() == Side::UP.axis() || r#move.turns == Turn::TWO
+            }));
+        }
+        assert!(domino_finishes::<SimpleMove>(&cube, Side::RIGHT, 0).is_empty());
+    }
+
+    #[test]
+    fn solves_by_orienting_edges_and_reducing_to_a_domino() {
+        let scramble = sequence("D2 F' R2 U L2 B' R D' F2 L U2 B R' F D2 R2 B2 U");
+        let cube = Cube::create_solved().apply(&scramble);
+        let orientation = edge_orientations::<SimpleMove>(&cube, 0).remove(0);
+        let oriented = cube.apply(&orientation.moves);
+        let reduction = domino_reductions::<SimpleMove>(&oriented, orientation.axis, 0).remove(0);
+        let reduced = oriented.apply(&reduction.moves);
+        let finish = domino_finishes::<SimpleMove>(&reduced, reduction.axis, 0).remove(0);
+        assert_eq!(reduced.apply(&finish), Cube::create_solved());
+    }
+
+    #[test]
+    fn inserts_a_corner_commutator() {
+        // The skeleton leaves `[R U R', D]` undone.
+        let scramble = sequence("R U R' D R U' R' D' U2 F");
+        let skeleton = sequence("F' U2");
+        let found = insertions(&scramble, &skeleton);
+        assert!(!found.is_empty());
+        for insertions in &found {
+            assert_eq!(insertions.insertions.len(), 1);
+            assert_eq!(
+                Cube::create_solved()
+                    .apply(&scramble)
+                    .apply(&insertions.solution),
+                Cube::create_solved()
+            );
+        }
+        assert!(found[0].solution.len() <= 10, "{}", found[0].solution);
+        assert!(
+            found
+                .windows(2)
+                .all(|pair| pair[0].solution.len() <= pair[1].solution.len())
+        );
+    }
+
+    #[test]
+    fn inserts_one_commutator_per_cycle() {
+        // A corner 3-cycle and a U perm.
+        let scramble = sequence("R U R' D R U' R' D' R2 U R U R' U' R' U' R' U R'");
+        let found = insertions(&scramble, &Sequence::new());
+        assert!(!found.is_empty());
+        assert!(
+            found
+                .iter()
+                .all(|insertions| insertions.insertions.len() == 2)
+        );
+        assert_eq!(
+            Cube::create_solved()
+                .apply(&scramble)
+                .apply(&found[0].solution),
+            Cube::create_solved()
+        );
+
+        // A single twisted corner isn't a 3-cycle.
+        let twisted = sequence("R' D' R D R' D' R D");
+        assert!(insertions(&twisted, &Sequence::new()).is_empty());
+    }
+
+    #[test]
+    fn inserts_two_commutators_for_what_a_3_cycle_cannot_solve() {
+        for scramble in [
+            // Two twisted corners.
+            "R' D' R D R' D' R D U R' D' R D R' D' R D R' D' R D R' D' R D U'",
+            // Two pairs of swapped corners.
+            "R U R' D R U' R' D' R U R' D2 R U' R' D2",
+            // Two flipped edges.
+            "U' R' F2 B2 L F L' B2 F2 R F' U B U2 D2 F' U F D2 U2 B' U'",
+        ] {
+            let scramble = sequence(scramble);
+            let found = insertions(&scramble, &Sequence::new());
+            assert!(!found.is_empty(), "{scramble}");
+            for insertions in &found {
+                assert_eq!(insertions.insertions.len(), 2);
+                assert_eq!(
+                    Cube::create_solved()
+                        .apply(&scramble)
+                        .apply(&insertions.solution),
+                    Cube::create_solved()
+                );
+            }
+        }
+    }
+}
@@ -0,0 +1,271 @@
+use std::{collections::HashSet, fmt::Display, hash::Hash};
+
+use crate::{
+    cube::{
+        Cube,
+        mask::Mask,
+        r#move::Move,
+        move_sequence::Sequence,
+        simple_move::{Side, Turn},
+    },
+    solver::Search,
+};
+
+/// Finds algorithms for a case, the way alg-hunters use Cube Explorer: every
+/// solution within a few moves of the best one, in the moves and the cost
+/// model of a `Search`, ranked by cost.
+///
+/// The last layer is on `U`. With `pre_auf` and `post_auf`, it may be turned
+/// before and after the algorithm, and algorithms starting or ending with a
+/// `U` turn are left out, since they're another algorithm with a different
+/// turn of the last layer.
+#[derive(Debug, Clone)]
+pub struct Generator<T: Move> {
+    search: Search<T>,
+    goal: Mask,
+    extra: usize,
+    pre_auf: bool,
+    post_auf: bool,
+    limit: usize,
+}
+
+/// One algorithm found by a `Generator`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Candidate<T: Move> {
+    /// The turn of the last layer before the algorithm.
+    pub pre_auf: Option<Turn>,
+    pub algorithm: Sequence<T>,
+    /// The turn of the last layer after the algorithm.
+    pub post_auf: Option<Turn>,
+    /// What the algorithm costs, not counting the turns of the last layer.
+    pub cost: usize,
+}
+
+impl<T: Move + Eq + Hash> Generator<T> {
+    pub fn new() -> Self {
+        Generator {
+            search: Search::new(),
+            goal: Mask::all(),
+            extra: 2,
+            pre_auf: true,
+            post_auf: true,
+            limit: 20,
+        }
+    }
+
+    /// Search with the moves and the cost model of `search`. Its goal and
+    /// extra moves are replaced by the generator's.
+    pub fn search(mut self, search: Search<T>) -> Self {
+        self.search = search;
+        self
+    }
+
+    /// Only solve the pieces `goal` cares about, the whole cube by default.
+    pub fn goal(mut self, goal: Mask) -> Self {
+        self.goal = goal;
+        self
+    }
+
+    /// Also find algorithms costing up to `extra` more than the cheapest one,
+    /// 2 by default.
+    pub fn extra(mut self, extra: usize) -> Self {
+        self.extra = extra;
+        self
+    }
+
+    /// Whether the last layer may be turned before the algorithm. On by
+    /// default.
+    pub fn pre_auf(mut self, pre_auf: bool) -> Self {
+        self.pre_auf = pre_auf;
+        self
+    }
+
+    /// Whether the last layer may be turned after the algorithm. On by
+    /// default.
+    pub fn post_auf(mut self, post_auf: bool) -> Self {
+        self.post_auf = post_auf;
+        self
+    }
+
+    /// Keep at most `limit` algorithms, 20 by default.
+    pub fn limit(mut self, limit: usize) -> Self {
+        self.limit = limit;
+        self
+    }
+
+    /// The algorithms solving `cube`, cheapest first, then shortest.
+    pub fn generate(&self, cube: &Cube) -> Vec<Candidate<T>> {
+        let turns = |enabled: bool| -> Vec<Option<Turn>> {
+            if enabled {
+                vec![None, Some(Turn::RIGHT), Some(Turn::TWO), Some(Turn::LEFT)]
+            } else {
+                vec![None]
+            }
+        };
+        let search = self
+            .search
+            .clone()
+            .goal(self.goal.clone())
+            .extra(self.extra);
+        let mut seen = HashSet::new();
+        let mut candidates = Vec::new();
+        for pre_auf in turns(self.pre_auf) {
+            for post_auf in turns(self.post_auf) {
+                // Turning the last layer after the algorithm is the same as
+                // turning it before the case, for goals that don't mind which
+                // way the last layer faces. Other goals are filtered below.
+                let start = Cube::create_solved()
+                    .apply(&auf::<T>(post_auf))
+                    .compose(cube)
+                    .apply(&auf::<T>(pre_auf));
+                for algorithm in search.solutions(start) {
+                    let solved = cube
+                        .apply(&auf::<T>(pre_auf))
+                        .apply(&algorithm)
+                        .apply(&auf::<T>(post_auf));
+                    if !self.goal.matches(&solved) || self.turns_last_layer(&algorithm) {
+                        continue;
+                    }
+                    if seen.insert(algorithm.clone()) {
+                        candidates.push(Candidate {
+                            pre_auf,
+                            cost: search.cost(&algorithm),
+                            algorithm,
+                            post_auf,
+                        });
+                    }
+                }
+            }
+        }
+
+        // Each turn of the last layer has its own optimum, so only keep what's
+        // within the extra moves of the best of all.
+        candidates.sort_by_key(|candidate| (candidate.cost, candidate.algorithm.len()));
+        let best = candidates.first().map_or(0, |candidate| candidate.cost);
+        candidates.retain(|candidate| candidate.cost <= best + self.extra);
+        candidates.truncate(self.limit);
+        candidates
+    }
+
+    /// Whether the algorithm starts or ends with a turn the AUFs already make.
+    fn turns_last_layer(&self, algorithm: &Sequence<T>) -> bool {
+        let moves = algorithm.moves();
+        let turns_up = |r#move: Option<&T>| r#move.is_some_and(|r#move| r#move.side() == Side::UP);
+        (self.pre_auf && turns_up(moves.first())) || (self.post_auf && turns_up(moves.last()))
+    }
+}
+
+impl<T: Move + Eq + Hash> Default for Generator<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+fn auf<T: Move>(turns: Option<Turn>) -> Sequence<T> {
+    turns
+        .map(|turns| T::from_parts(Side::UP, turns))
+        .into_iter()
+        .collect()
+}
+
+/// Written like Cube Explorer does, with the turns of the last layer in
+/// parentheses.
+impl<T: Move + Display> Display for Candidate<T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if let Some(pre_auf) = self.pre_auf {
+            write!(f, "({}) ", auf::<T>(Some(pre_auf)))?;
+        }
+        write!(f, "{}", self.algorithm)?;
+        if let Some(post_auf) = self.post_auf {
+            write!(f, " ({})", auf::<T>(Some(post_auf)))?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        cube::{mask::Care, simple_move::SimpleMove},
+        solver::{cost::Ergonomics, subgroup::Subgroup},
+    };
+
+    fn case(algorithm: &str) -> Cube {
+        let algorithm: Sequence<SimpleMove> = algorithm.parse().unwrap();
+        Cube::create_solved().apply(&algorithm.invert())
+    }
+
+    #[test]
+    fn finds_ranked_algorithms_up_to_auf() {
+        let cube = case("U R U R' U R U2 R' U2");
+        let two_gen: Subgroup<SimpleMove> = "<R,U>".parse().unwrap();
+        let candidates = Generator::new()
+            .search(Search::new().subgroup(two_gen))
+            .generate(&cube);
+
+        let algorithms: Vec<String> = candidates
+            .iter()
+            .map(|candidate| candidate.algorithm.to_string())
+            .collect();
+        assert!(
+            algorithms.contains(&"R U R' U R U2 R'".to_string()),
+            "{algorithms:?}"
+        );
+        assert!(
+            candidates
+                .windows(2)
+                .all(|pair| pair[0].cost <= pair[1].cost)
+        );
+        for candidate in &candidates {
+            let solved = cube
+                .apply(&auf::<SimpleMove>(candidate.pre_auf))
+                .apply(&candidate.algorithm)
+                .apply(&auf::<SimpleMove>(candidate.post_auf));
+            assert_eq!(solved, Cube::create_solved(), "{candidate}");
+            assert!(candidate.cost <= candidates[0].cost + 2);
+            let moves = candidate.algorithm.moves();
+            assert_ne!(moves[0].side(), Side::UP, "{candidate}");
+            assert_ne!(moves[moves.len() - 1].side(), Side::UP, "{candidate}");
+            assert!(
+                moves
+                    .iter()
+                    .all(|r#move| [Side::UP, Side::RIGHT].contains(&r#move.side()))
+            );
+        }
+    }
+
+    #[test]
+    fn ranks_by_ergonomic_cost() {
+        let cube = case("R U R' U'");
+        let candidates = Generator::<SimpleMove>::new()
+            .search(Search::new().cost_model(Ergonomics::speedsolving()))
+            .extra(0)
+            .pre_auf(false)
+            .post_auf(false)
+            .generate(&cube);
+        assert_eq!(candidates.len(), 1);
+        assert_eq!(candidates[0].to_string(), "R U R' U'");
+        assert_eq!(candidates[0].cost, 4 * 8);
+    }
+
+    #[test]
+    fn solves_masked_goals() {
+        let cube = case("F R U R' U' F'");
+        let oriented = Mask::first_two_layers_on(Side::DOWN)
+            .with_corners(&[0, 1, 2, 3], Care::Orientation)
+            .with_edges(&[0, 1, 2, 3], Care::Orientation);
+        let candidates = Generator::<SimpleMove>::new()
+            .goal(oriented.clone())
+            .extra(0)
+            .generate(&cube);
+        assert!(!candidates.is_empty());
+        for candidate in &candidates {
+            assert_eq!(candidate.algorithm.len(), 6, "{candidate}");
+            let solved = cube
+                .apply(&auf::<SimpleMove>(candidate.pre_auf))
+                .apply(&candidate.algorithm);
+            assert!(oriented.matches(&solved), "{candidate}");
+        }
+    }
+}
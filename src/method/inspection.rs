@@ -0,0 +1,154 @@
+use crate::{
+    cube::{Cube, color::Color, mask::Mask, r#move::Move, move_sequence::Sequence},
+    solver::Search,
+};
+
+const COLORS: [Color; 6] = [
+    Color::WHITE,
+    Color::YELLOW,
+    Color::GREEN,
+    Color::BLUE,
+    Color::RED,
+    Color::ORANGE,
+];
+
+/// The optimal first steps with one color on the bottom.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Analysis<T: Move> {
+    pub color: Color,
+    pub cross: Sequence<T>,
+    /// The cross with one F2L pair, whichever is quickest.
+    pub x_cross: Sequence<T>,
+    /// Roux's first block on whichever side next to `color` is quickest.
+    pub first_block: Sequence<T>,
+}
+
+/// The optimal cross, X-cross and first block for every color, the way a
+/// color neutral solver would compare them during inspection.
+///
+/// `search` sets the metric, the subgroup, where pruning tables are kept and
+/// how deep to look, its goal is replaced. `None` if a step can't be solved
+/// within the search's maximum depth or subgroup.
+pub fn analyze<T: Move>(cube: &Cube, search: &Search<T>) -> Option<Vec<Analysis<T>>> {
+    COLORS
+        .iter()
+        .map(|&color| {
+            Some(Analysis {
+                color,
+                cross: cross(cube, color, search)?,
+                x_cross: x_cross(cube, color, search)?,
+                first_block: first_block(cube, color, search)?,
+            })
+        })
+        .collect()
+}
+
+pub fn cross<T: Move>(cube: &Cube, color: Color, search: &Search<T>) -> Option<Sequence<T>> {
+    shortest(cube, [Mask::cross_on(color.side())], search)
+}
+
+pub fn x_cross<T: Move>(cube: &Cube, color: Color, search: &Search<T>) -> Option<Sequence<T>> {
+    let side = color.side();
+    let goals = Mask::slots(side)
+        .into_iter()
+        .map(|slot| Mask::cross_on(side).union(&Mask::pair(side, slot)));
+    shortest(cube, goals, search)
+}
+
+pub fn first_block<T: Move>(cube: &Cube, color: Color, search: &Search<T>) -> Option<Sequence<T>> {
+    let bottom = color.side();
+    let goals = COLORS
+        .iter()
+        .map(|color| color.side())
+        .filter(|side| side.axis() != bottom.axis())
+        .map(|side| Mask::block(side, bottom));
+    shortest(cube, goals, search)
+}
+
+/// The cheapest way to any of `goals`. Each search after the first only
+/// looks for something cheaper than the best so far.
+fn shortest<T: Move>(
+    cube: &Cube,
+    goals: impl IntoIterator<Item = Mask>,
+    search: &Search<T>,
+) -> Option<Sequence<T>> {
+    let mut best: Option<Sequence<T>> = None;
+    for goal in goals {
+        let max_depth = match &best {
+            Some(moves) => match search.cost(moves).checked_sub(1) {
+                Some(max_depth) => max_depth,
+                None => break,
+            },
+            None => search.depth_limit(),
+        };
+        let moves = search
+            .clone()
+            .goal(goal)
+            .max_depth(max_depth)
+            .solve(cube.clone());
+        best = moves.or(best);
+    }
+    best
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cube::{scrambled, simple_move::SimpleMove};
+
+    #[test]
+    fn finds_the_optimal_steps_for_every_color() {
+        let cube = scrambled("R U F' L2 D B' R2 U' F D2 L' B U2 R' F2");
+        let search = Search::new();
+        let analyses = analyze::<SimpleMove>(&cube, &search).unwrap();
+        assert_eq!(analyses.len(), 6);
+        for analysis in &analyses {
+            let side = analysis.color.side();
+            assert!(Mask::cross_on(side).matches(&cube.apply(&analysis.cross)));
+            let optimal = Search::<SimpleMove>::new()
+                .goal(Mask::cross_on(side))
+                .solve(cube.clone())
+                .unwrap();
+            assert_eq!(analysis.cross.len(), optimal.len());
+
+            let x_crossed = cube.apply(&analysis.x_cross);
+            assert!(Mask::cross_on(side).matches(&x_crossed));
+            assert!(
+                Mask::slots(side)
+                    .into_iter()
+                    .any(|slot| Mask::pair(side, slot).matches(&x_crossed))
+            );
+            assert!(analysis.x_cross.len() >= analysis.cross.len());
+
+            let blocked = cube.apply(&analysis.first_block);
+            assert!(
+                COLORS
+                    .iter()
+                    .map(|color| color.side())
+                    .filter(|block| block.axis() != side.axis())
+                    .any(|block| Mask::block(block, side).matches(&blocked))
+            );
+        }
+    }
+
+    #[test]
+    fn keeps_the_search_limit() {
+        let cube = scrambled("R U F' L2 D B'");
+        let search = Search::<SimpleMove>::new().max_depth(1);
+        assert!(cross(&cube, Color::WHITE, &search).is_none());
+        assert!(analyze(&cube, &search).is_none());
+    }
+
+    #[test]
+    fn picks_the_quickest_slot_and_block() {
+        let cube = scrambled("R");
+        let search = Search::<SimpleMove>::new();
+        for color in [Color::YELLOW, Color::WHITE, Color::GREEN] {
+            assert_eq!(cross(&cube, color, &search).unwrap().len(), 1);
+            assert_eq!(x_cross(&cube, color, &search).unwrap().len(), 1);
+            assert_eq!(first_block(&cube, color, &search).unwrap().len(), 0);
+        }
+        assert_eq!(cross(&cube, Color::ORANGE, &search).unwrap().len(), 0);
+        assert_eq!(x_cross(&cube, Color::ORANGE, &search).unwrap().len(), 0);
+    }
+}
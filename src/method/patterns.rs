@@ -0,0 +1,191 @@
+use crate::{
+    cube::{Cube, r#move::Move, move_sequence::Sequence, notation, simple_move::SimpleMove},
+    solver::Search,
+};
+
+/// A well-known pretty pattern and a way to turn it from the solved cube.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Pattern {
+    pub name: &'static str,
+    pub notation: &'static str,
+}
+
+/// The built-in patterns.
+pub const PATTERNS: [Pattern; 12] = [
+    Pattern {
+        name: "Superflip",
+        notation: "U R2 F B R B2 R U2 L B2 R U' D' R2 F R' L B2 U2 F2",
+    },
+    Pattern {
+        name: "Checkerboard",
+        notation: "U2 D2 F2 B2 R2 L2",
+    },
+    Pattern {
+        name: "Cube in a cube",
+        notation: "F L F U' R U F2 L2 U' L' B D' B' L2 U",
+    },
+    Pattern {
+        name: "Cube in a cube in a cube",
+        notation: "U' L' U' F' R2 B' R F U B2 U B' L U' F U R F'",
+    },
+    Pattern {
+        name: "Six spots",
+        notation: "U D' R L' F B' U D'",
+    },
+    Pattern {
+        name: "Four spots",
+        notation: "F2 B2 U D' R2 L2 U D'",
+    },
+    Pattern {
+        name: "Plus minus",
+        notation: "U2 R2 L2 U2 R2 L2",
+    },
+    Pattern {
+        name: "Cross",
+        notation: "U F B' L2 U2 L2 F' B U2 L2 U",
+    },
+    Pattern {
+        name: "Wire",
+        notation: "R L F B R L F B R L F B R2 B2 L2 R2 B2 L2",
+    },
+    Pattern {
+        name: "Vertical stripes",
+        notation: "F U F R L2 B D' R D2 L D' B R2 L F U F",
+    },
+    Pattern {
+        name: "Tetris",
+        notation: "L R F B U' D' L' R'",
+    },
+    Pattern {
+        name: "Anaconda",
+        notation: "L U B' U' R L' B R' F B' D R D' F'",
+    },
+];
+
+impl Pattern {
+    /// The built-in pattern called `name`, ignoring case.
+    pub fn named(name: &str) -> Option<Pattern> {
+        PATTERNS
+            .into_iter()
+            .find(|pattern| pattern.name.eq_ignore_ascii_case(name))
+    }
+
+    pub fn moves<T: Move>(&self) -> Sequence<T> {
+        notation::expand(self.notation).expect("built-in patterns are valid notation")
+    }
+
+    /// The cube showing the pattern.
+    pub fn cube(&self) -> Cube {
+        Cube::create_solved().apply(&self.moves::<SimpleMove>())
+    }
+
+    pub fn matches(&self, cube: &Cube) -> bool {
+        *cube == self.cube()
+    }
+
+    /// The shortest moves turning `cube` into the pattern, in the metric of
+    /// `search`, or `None` if it takes more than its maximum depth.
+    ///
+    /// `search` solves `cube` as seen from the pattern, so it should keep the
+    /// solved cube as its goal.
+    pub fn shortest_from<T: Move>(&self, cube: &Cube, search: &Search<T>) -> Option<Sequence<T>> {
+        search.solve(self.cube().inverse().compose(cube))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cube::color::Color;
+
+    #[test]
+    fn patterns_are_distinct_and_unsolved() {
+        for (index, pattern) in PATTERNS.iter().enumerate() {
+            assert!(pattern.cube().is_solvable(), "{}", pattern.name);
+            assert!(!pattern.matches(&Cube::create_solved()), "{}", pattern.name);
+            for other in &PATTERNS[index + 1..] {
+                assert_ne!(pattern.cube(), other.cube(), "{}", pattern.name);
+            }
+        }
+        assert_eq!(Pattern::named("checkerboard"), Some(PATTERNS[1]));
+        assert_eq!(Pattern::named("Rubik"), None);
+    }
+
+    #[test]
+    fn superflip_flips_every_edge_in_place() {
+        let cube = Pattern::named("Superflip").unwrap().cube();
+        assert_eq!(cube.edge_orientation(), &[1; 12]);
+        let solved = Cube::create_solved();
+        assert_eq!(cube.edge_permutation(), solved.edge_permutation());
+        assert_eq!(cube.corner_permutation(), solved.corner_permutation());
+        assert_eq!(cube.corner_orientation(), solved.corner_orientation());
+    }
+
+    #[test]
+    fn checkerboard_alternates_every_face() {
+        let faces = Pattern::named("Checkerboard").unwrap().cube().faces();
+        for face in faces {
+            for (index, color) in face.iter().enumerate() {
+                assert_eq!(*color == face[4], index % 2 == 0);
+            }
+        }
+    }
+
+    #[test]
+    fn spots_leave_only_the_centers() {
+        let spotted = |pattern: &str| -> Vec<bool> {
+            Pattern::named(pattern)
+                .unwrap()
+                .cube()
+                .faces()
+                .iter()
+                .map(|face: &[Color; 9]| {
+                    let around: Vec<&Color> =
+                        face.iter().filter(|color| **color != face[4]).collect();
+                    around.len() == 8 && around.iter().all(|color| *color == around[0])
+                })
+                .collect()
+        };
+        assert_eq!(spotted("Six spots"), [true; 6]);
+        assert_eq!(
+            spotted("Four spots"),
+            [false, false, true, true, true, true]
+        );
+    }
+
+    #[test]
+    fn cube_in_a_cube_shows_a_smaller_cube_at_a_corner() {
+        let [up, _, front, _, right, _] = Pattern::named("Cube in a cube").unwrap().cube().faces();
+        // The quarter of each face around `UFR`, as laid out by `faces`.
+        for (face, quarter) in [
+            (up, [4, 5, 7, 8]),
+            (front, [1, 2, 4, 5]),
+            (right, [0, 1, 3, 4]),
+        ] {
+            for (index, color) in face.iter().enumerate() {
+                assert_eq!(*color == face[4], quarter.contains(&index));
+            }
+        }
+    }
+
+    #[test]
+    fn finds_the_shortest_way_to_a_pattern() {
+        let checkerboard = Pattern::named("Checkerboard").unwrap();
+        let scramble: Sequence<SimpleMove> = "U2 F".parse().unwrap();
+        let cube = Cube::create_solved().apply(&scramble);
+        let moves: Sequence<SimpleMove> =
+            checkerboard.shortest_from(&cube, &Search::new()).unwrap();
+        assert!(checkerboard.matches(&cube.apply(&moves)));
+        assert!(moves.len() <= 6, "{moves}");
+
+        let solved = Cube::create_solved();
+        let moves: Sequence<SimpleMove> =
+            checkerboard.shortest_from(&solved, &Search::new()).unwrap();
+        assert_eq!(moves.len(), 6);
+        assert!(
+            checkerboard
+                .shortest_from(&solved, &Search::<SimpleMove>::new().max_depth(5))
+                .is_none()
+        );
+    }
+}
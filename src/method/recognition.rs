@@ -0,0 +1,163 @@
+use crate::{
+    cube::{
+        Cube,
+        mask::{Care, Mask},
+        r#move::Move,
+        move_sequence::Sequence,
+        simple_move::Side,
+    },
+    method::{
+        algorithms::Set,
+        cfop::{OLL, PLL},
+        last_layer_stage,
+    },
+};
+
+/// A named last layer case and the algorithm that solves it.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Case<T: Move> {
+    /// `"skip"` when there is nothing to solve.
+    pub name: &'static str,
+    /// The algorithm as it's written, after the rotation that puts the last
+    /// layer on top and with the turns of the last layer it needs.
+    pub algorithm: String,
+    /// The same in face turns.
+    pub moves: Sequence<T>,
+}
+
+/// The OLL case of a cube with its first two layers solved, on any side.
+pub fn oll<T: Move>(cube: &Cube) -> Option<Case<T>> {
+    let side = first_two_layers(cube)?;
+    let last_layer = side.opposite() as usize;
+    let oriented = |cube: &Cube| {
+        let face = cube.faces()[last_layer];
+        face.iter().all(|color| *color == face[4])
+    };
+    last_layer_stage(cube, side, &OLL, oriented, false)
+}
+
+/// The PLL case of a cube with its first two layers solved and its last
+/// layer oriented. `None` while the last layer still needs OLL.
+pub fn pll<T: Move>(cube: &Cube) -> Option<Case<T>> {
+    let side = first_two_layers(cube)?;
+    last_layer_stage(cube, side, &PLL, |cube| Mask::all().matches(cube), true)
+}
+
+/// The COLL case of a cube with its first two layers solved and the edges of
+/// its last layer oriented. `None` while an edge still needs orienting.
+pub fn coll<T: Move>(cube: &Cube) -> Option<Case<T>> {
+    let side = first_two_layers(cube)?;
+    let corners =
+        Mask::first_two_layers_on(side).with_corners(&[0, 1, 2, 3, 4, 5, 6, 7], Care::Full);
+    let last_layer = side.opposite() as usize;
+    let goal = |cube: &Cube| {
+        let face = cube.faces()[last_layer];
+        corners.matches(cube) && face.iter().all(|color| *color == face[4])
+    };
+    last_layer_stage(cube, side, Set::COLL.built_in(), goal, true)
+}
+
+/// The ZBLL case of a cube with its first two layers solved and the edges of
+/// its last layer oriented, or its PLL case once the corners are oriented
+/// too. `None` while an edge still needs orienting.
+pub fn zbll<T: Move>(cube: &Cube) -> Option<Case<T>> {
+    let side = first_two_layers(cube)?;
+    let algorithms: Vec<(&str, &str)> = Set::ZBLL.built_in().iter().chain(&PLL).copied().collect();
+    last_layer_stage(
+        cube,
+        side,
+        &algorithms,
+        |cube| Mask::all().matches(cube),
+        true,
+    )
+}
+
+/// The side the first two layers are solved on.
+fn first_two_layers(cube: &Cube) -> Option<Side> {
+    [
+        Side::DOWN,
+        Side::UP,
+        Side::FRONT,
+        Side::BACK,
+        Side::RIGHT,
+        Side::LEFT,
+    ]
+    .into_iter()
+    .find(|&side| Mask::first_two_layers_on(side).matches(cube))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cube::{notation, simple_move::SimpleMove};
+
+    /// The cube that `algorithm` solves.
+    fn solved_by(algorithm: &str) -> Cube {
+        let algorithm: Sequence<SimpleMove> = notation::expand(algorithm).unwrap();
+        Cube::create_solved().apply(&algorithm.invert())
+    }
+
+    #[test]
+    fn recognizes_oll_up_to_auf() {
+        let cube = solved_by("U R U R' U R U2 R'");
+        let case: Case<SimpleMove> = oll(&cube).unwrap();
+        assert_eq!(case.name, "27");
+        assert_eq!(case.algorithm, "U R U R' U R U2 R'");
+        assert!(pll::<SimpleMove>(&cube).is_none());
+
+        let oriented = cube.apply(&case.moves);
+        assert_eq!(oll::<SimpleMove>(&oriented).unwrap().name, "skip");
+        assert_eq!(pll::<SimpleMove>(&oriented).unwrap().name, "skip");
+    }
+
+    #[test]
+    fn recognizes_pll_with_the_last_layer_on_any_side() {
+        let cube = solved_by("y R U R' U' R' F R2 U' R' U' R U R' F' U2 y'");
+        let case: Case<SimpleMove> = pll(&cube).unwrap();
+        assert_eq!(case.name, "T");
+        assert_eq!(cube.apply(&case.moves), Cube::create_solved());
+
+        let cube = solved_by("x2 U M2 U M2 U2 M2 U M2 x2");
+        let case: Case<SimpleMove> = pll(&cube).unwrap();
+        assert_eq!(case.name, "H");
+        assert!(case.algorithm.starts_with("x2"));
+        assert_eq!(cube.apply(&case.moves), Cube::create_solved());
+    }
+
+    #[test]
+    fn recognizes_coll_and_zbll_with_the_last_layer_on_any_side() {
+        let (name, algorithm) = Set::COLL.built_in()[10];
+        let cube = solved_by(&format!("y U {algorithm} U2 y'"));
+        let case: Case<SimpleMove> = coll(&cube).unwrap();
+        assert_eq!(case.name, name);
+        let corners = cube.apply(&case.moves);
+        assert!(Set::COLL.goal().matches(&corners));
+        assert_eq!(coll::<SimpleMove>(&corners).unwrap().name, "skip");
+        assert!(pll::<SimpleMove>(&corners).is_some());
+
+        let (name, algorithm) = Set::ZBLL.built_in()[100];
+        let cube = solved_by(&format!("x2 U' {algorithm} x2"));
+        let case: Case<SimpleMove> = zbll(&cube).unwrap();
+        assert_eq!(case.name, name);
+        assert!(case.algorithm.starts_with("x2"));
+        assert_eq!(cube.apply(&case.moves), Cube::create_solved());
+
+        let cube = solved_by("y R U R' U' R' F R2 U' R' U' R U R' F' U2 y'");
+        assert_eq!(zbll::<SimpleMove>(&cube).unwrap().name, "T");
+    }
+
+    #[test]
+    fn needs_oriented_edges() {
+        let cube = solved_by("R U R' U' M' U R U' r'");
+        assert!(oll::<SimpleMove>(&cube).is_some());
+        assert!(coll::<SimpleMove>(&cube).is_none());
+        assert!(zbll::<SimpleMove>(&cube).is_none());
+    }
+
+    #[test]
+    fn needs_the_first_two_layers() {
+        let cube = solved_by("R U F");
+        assert!(oll::<SimpleMove>(&cube).is_none());
+        assert!(pll::<SimpleMove>(&cube).is_none());
+    }
+}
@@ -0,0 +1,341 @@
+use crate::{
+    cube::{
+        Cube,
+        mask::{Care, Mask},
+        metric::Metric,
+        r#move::Move,
+        notation,
+        simple_move::Side,
+    },
+    method::{Solution, Stage},
+};
+
+const SIDES: [Side; 6] = [
+    Side::DOWN,
+    Side::UP,
+    Side::FRONT,
+    Side::BACK,
+    Side::RIGHT,
+    Side::LEFT,
+];
+
+/// The methods a reconstruction can be split by.
+#[allow(clippy::upper_case_acronyms)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Method {
+    CFOP,
+    Roux,
+    ZZ,
+}
+
+/// A human solution split into the stages of its method.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Reconstruction<T: Move> {
+    pub solution: Solution<T>,
+    /// Whether the solution solves the scramble. If not, the last stage holds
+    /// the moves after the last stage that was finished.
+    pub solved: bool,
+}
+
+impl<T: Move> Reconstruction<T> {
+    /// The moves of the solution as solvers count them, see `Stage::length`.
+    pub fn length(&self) -> usize {
+        self.length_in(Metric::STM)
+    }
+
+    /// The moves of the solution as written, counted in `metric`.
+    pub fn length_in(&self, metric: Metric) -> usize {
+        self.solution
+            .stages
+            .iter()
+            .map(|stage| stage.length_in(metric))
+            .sum()
+    }
+
+    /// Turns per second, for a solve that took `seconds`.
+    pub fn tps(&self, seconds: f64) -> f64 {
+        self.length() as f64 / seconds
+    }
+}
+
+/// Splits `solution`, written the way the solver turned the cube, into the
+/// stages of `method`, each ending with the move that finished it.
+///
+/// The cube can be held any way: every color of cross, every side for the
+/// Roux blocks and every bottom for ZZ that keeps the edges oriented along
+/// `F` and `B` is tried, and the one that finishes its stages earliest is
+/// taken. Stages finished by the same move as the one before them, like a
+/// skipped OLL, have no moves. `None` if either sequence has a move that
+/// isn't understood.
+pub fn reconstruct<T: Move>(
+    scramble: &str,
+    solution: &str,
+    method: Method,
+) -> Option<Reconstruction<T>> {
+    let scrambled = Cube::create_solved().apply(&notation::expand::<T>(scramble).ok()?);
+    let moves = notation::split(solution);
+    // The face turns and the cube after each number of moves.
+    let expanded = (0..=moves.len())
+        .map(|end| notation::expand::<T>(&moves[..end].join(" ")).ok())
+        .collect::<Option<Vec<_>>>()?;
+    let cubes: Vec<Cube> = expanded
+        .iter()
+        .map(|expanded| scrambled.apply(expanded))
+        .collect();
+
+    let finished = |milestones: &[Milestone]| {
+        let mut start = 0;
+        milestones
+            .iter()
+            .map_while(|(_, done)| {
+                start += cubes[start..].iter().position(done)?;
+                Some(start)
+            })
+            .collect::<Vec<usize>>()
+    };
+    let (milestones, ends) = candidates(method)
+        .into_iter()
+        .map(|milestones| {
+            let ends = finished(&milestones);
+            (milestones, ends)
+        })
+        .min_by_key(|(milestones, ends)| {
+            let unfinished = (milestones.len() - ends.len()) * (moves.len() + 1);
+            ends.iter().sum::<usize>() + unfinished
+        })?;
+
+    let solved = ends.len() == milestones.len();
+    let mut ends = ends;
+    match ends.last_mut() {
+        // Anything after the last stage, like a final rotation, belongs to it.
+        Some(last) if solved => *last = moves.len(),
+        _ => ends.push(moves.len()),
+    }
+    let mut stages = Vec::new();
+    let mut start = 0;
+    for ((name, _), end) in milestones.iter().zip(ends) {
+        stages.push(Stage {
+            name: name.to_string(),
+            notation: moves[start..end].join(" "),
+            moves: expanded[end].moves()[expanded[start].len()..]
+                .iter()
+                .cloned()
+                .collect(),
+        });
+        start = end;
+    }
+    Some(Reconstruction {
+        solution: Solution { stages },
+        solved,
+    })
+}
+
+/// A stage and whether a cube has it done.
+type Milestone = (String, Box<dyn Fn(&Cube) -> bool>);
+
+/// The stages of `method`, for each way of holding the cube.
+fn candidates(method: Method) -> Vec<Vec<Milestone>> {
+    match method {
+        Method::CFOP => SIDES.into_iter().map(cfop).collect(),
+        Method::Roux => SIDES.into_iter().map(roux).collect(),
+        Method::ZZ => [Side::DOWN, Side::UP, Side::RIGHT, Side::LEFT]
+            .into_iter()
+            .map(zz)
+            .collect(),
+    }
+}
+
+fn cfop(side: Side) -> Vec<Milestone> {
+    let mut milestones: Vec<Milestone> = vec![(
+        "Cross".to_string(),
+        Box::new(move |cube| Mask::cross_on(side).matches(cube)),
+    )];
+    for pairs in 1..=4 {
+        milestones.push((
+            format!("F2L {pairs}"),
+            Box::new(move |cube| {
+                let solved = Mask::slots(side)
+                    .into_iter()
+                    .filter(|&corner| Mask::pair(side, corner).matches(cube))
+                    .count();
+                Mask::cross_on(side).matches(cube) && solved >= pairs
+            }),
+        ));
+    }
+    milestones.extend(last_layer(side, "OLL"));
+    milestones
+}
+
+fn roux(side: Side) -> Vec<Milestone> {
+    // `M` turns move the centers between the blocks, so each stage may find
+    // them along any bottom.
+    let blocks = move |cube: &Cube, sides: &[Side], corners: bool| {
+        SIDES
+            .into_iter()
+            .filter(|bottom| bottom.axis() != side.axis())
+            .any(|bottom| {
+                let mut mask = Mask::none();
+                for &side in sides {
+                    mask = mask.union(&Mask::block(side, bottom));
+                }
+                if corners {
+                    mask = mask.with_corners(&Mask::slots(bottom.opposite()), Care::Full);
+                }
+                mask.matches(cube)
+            })
+    };
+    vec![
+        (
+            "First block".to_string(),
+            Box::new(move |cube| blocks(cube, &[side], false)),
+        ),
+        (
+            "Second block".to_string(),
+            Box::new(move |cube| blocks(cube, &[side, side.opposite()], false)),
+        ),
+        (
+            "CMLL".to_string(),
+            Box::new(move |cube| blocks(cube, &[side, side.opposite()], true)),
+        ),
+        (
+            "LSE".to_string(),
+            Box::new(|cube| Mask::all().matches(cube)),
+        ),
+    ]
+}
+
+fn zz(side: Side) -> Vec<Milestone> {
+    let eo_line = Mask::eo_line_on(side);
+    let blocks: Vec<Mask> = [Side::RIGHT, Side::LEFT, Side::UP, Side::DOWN]
+        .into_iter()
+        .filter(|block| block.axis() != side.axis())
+        .map(|block| Mask::block(block, side))
+        .collect();
+    let f2l = Mask::first_two_layers_on(side).union(&Mask::edge_orientation());
+    let mut milestones: Vec<Milestone> = vec![
+        (
+            "EOLine".to_string(),
+            Box::new(move |cube| eo_line.matches(cube)),
+        ),
+        (
+            "F2L 1".to_string(),
+            Box::new(move |cube| {
+                Mask::eo_line_on(side).matches(cube)
+                    && blocks.iter().any(|block| block.matches(cube))
+            }),
+        ),
+        ("F2L 2".to_string(), Box::new(move |cube| f2l.matches(cube))),
+    ];
+    milestones.extend(last_layer(side, "OCLL"));
+    milestones
+}
+
+/// Orienting the last layer across from `side`, then permuting it.
+fn last_layer(side: Side, orientation: &str) -> Vec<Milestone> {
+    vec![
+        (
+            orientation.to_string(),
+            Box::new(move |cube| {
+                let face = cube.faces()[side.opposite() as usize];
+                Mask::first_two_layers_on(side).matches(cube)
+                    && face.iter().all(|color| *color == face[4])
+            }),
+        ),
+        (
+            "PLL".to_string(),
+            Box::new(|cube| Mask::all().matches(cube)),
+        ),
+    ]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        cube::{color::Color, simple_move::SimpleMove},
+        method::cfop,
+    };
+
+    fn names(reconstruction: &Reconstruction<SimpleMove>) -> Vec<&str> {
+        reconstruction
+            .solution
+            .stages
+            .iter()
+            .map(|stage| &stage.name[..])
+            .collect()
+    }
+
+    #[test]
+    fn splits_a_cfop_solve() {
+        // A yellow cross solve, held with the cross on the bottom.
+        let scramble = "D2 F' R2 U L2 B' R D' F2 L U2 B R' F D2 R2 B2 U";
+        let cube: Cube =
+            Cube::create_solved().apply(&notation::expand::<SimpleMove>(scramble).unwrap());
+        let solution = cfop::solve::<SimpleMove>(&cube, Color::YELLOW).unwrap();
+        let written: Vec<String> = solution
+            .stages
+            .iter()
+            .map(|stage| stage.notation.clone())
+            .collect();
+        let reconstruction: Reconstruction<SimpleMove> =
+            reconstruct(scramble, &written.join(" "), Method::CFOP).unwrap();
+
+        assert!(reconstruction.solved);
+        assert_eq!(
+            names(&reconstruction),
+            ["Cross", "F2L 1", "F2L 2", "F2L 3", "F2L 4", "OLL", "PLL"]
+        );
+        assert_eq!(reconstruction.solution.moves(), solution.moves());
+        assert_eq!(reconstruction.length(), solution.moves().len());
+        assert_eq!(
+            reconstruction.tps(10.0),
+            reconstruction.length() as f64 / 10.0
+        );
+    }
+
+    #[test]
+    fn follows_rotations() {
+        // The last layer is turned upside down, so it's solved on `D`.
+        let reconstruction: Reconstruction<SimpleMove> =
+            reconstruct("F R U R' U' F'", "x2 (B D R D' R' B')", Method::CFOP).unwrap();
+        assert!(reconstruction.solved);
+        let stages = &reconstruction.solution.stages;
+        assert!(stages[..5].iter().all(|stage| stage.notation.is_empty()));
+        assert_eq!(stages[5].notation, "x2 B D R D' R' B'");
+        assert_eq!(stages[5].length(), 6);
+        assert_eq!(reconstruction.length_in(Metric::ETM), 7);
+        assert!(stages[6].notation.is_empty());
+    }
+
+    #[test]
+    fn splits_roux_with_slice_moves() {
+        let reconstruction: Reconstruction<SimpleMove> = reconstruct(
+            "M2 U M2 U2 M2 U M2 R U R' U'",
+            "U R U' R' M2 U' M2 U2 M2 U' M2",
+            Method::Roux,
+        )
+        .unwrap();
+        assert_eq!(
+            names(&reconstruction),
+            ["First block", "Second block", "CMLL", "LSE"]
+        );
+        let stages = &reconstruction.solution.stages;
+        assert_eq!(stages[1].notation, "U R U' R'");
+        assert_eq!(stages[3].length(), 7);
+        assert_eq!(stages[3].length_in(Metric::HTM), 11);
+        assert!(reconstruction.solved);
+    }
+
+    #[test]
+    fn keeps_unfinished_moves_in_the_next_stage() {
+        let reconstruction: Reconstruction<SimpleMove> =
+            reconstruct("F R U", "U' R'", Method::ZZ).unwrap();
+        assert!(!reconstruction.solved);
+        assert_eq!(names(&reconstruction).last(), Some(&"EOLine"));
+        assert_eq!(
+            reconstruction.solution.stages.last().unwrap().notation,
+            "U' R'"
+        );
+        assert!(reconstruct::<SimpleMove>("R", "Q", Method::ZZ).is_none());
+    }
+}
@@ -0,0 +1,117 @@
+use std::{fmt::Display, str::FromStr};
+
+use crate::{
+    cube::{
+        Cube, mask::Mask, metric::Metric, r#move::Move, move_sequence::Sequence, simple_move::Side,
+    },
+    method::{Solution, Stage, algorithms::Set, last_layer_stage},
+    solver::{Search, subgroup::Subgroup},
+};
+
+/// Solves `cube` the way a Roux solver would, held with yellow at the bottom
+/// and green in front: an optimal first block on `L`, the second block on `R`
+/// in `<R, U, M, r>`, CMLL from the built-in algorithms, then the last six
+/// edges in `<M, U>`. Both blocks and the last six edges are optimal in STM.
+pub fn solve<T: Move + Display + FromStr>(cube: &Cube) -> Option<Solution<T>> {
+    let mut cube = cube.clone();
+    let mut stages = Vec::new();
+
+    let mut solved = Mask::first_block();
+    let first_block: Sequence<T> = Search::new().goal(solved.clone()).solve(cube.clone())?;
+    cube = cube.apply(&first_block);
+    stages.push(Stage {
+        name: "First block".to_string(),
+        notation: first_block.to_string(),
+        moves: first_block,
+    });
+
+    solved = solved.union(&Mask::second_block());
+    let (notation, moves) = Search::new()
+        .subgroup("<R,U,M,r>".parse().ok()?)
+        .metric(Metric::STM)
+        .goal(solved)
+        .solve_written(cube.clone())?;
+    cube = cube.apply(&moves);
+    stages.push(Stage {
+        name: "Second block".to_string(),
+        notation,
+        moves,
+    });
+
+    let goal = Set::CMLL.goal();
+    let case = last_layer_stage(
+        &cube,
+        Side::DOWN,
+        Set::CMLL.built_in(),
+        |cube| goal.matches(cube),
+        true,
+    )?;
+    cube = cube.apply(&case.moves);
+    stages.push(Stage {
+        name: format!("CMLL {}", case.name),
+        notation: case.algorithm,
+        moves: case.moves,
+    });
+
+    let (notation, moves) = Search::new()
+        .subgroup("<M,U>".parse::<Subgroup<T>>().ok()?)
+        .metric(Metric::STM)
+        .solve_written(cube)?;
+    stages.push(Stage {
+        name: "LSE".to_string(),
+        notation,
+        moves,
+    });
+    Some(Solution { stages })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cube::simple_move::SimpleMove;
+
+    #[test]
+    fn solves_in_stages() {
+        let scramble: Sequence<SimpleMove> = "D2 F' R2 U L2 B' R D' F2 L U2 B R' F D2 R2 B2 U"
+            .parse()
+            .unwrap();
+        let cube = Cube::create_solved().apply(&scramble);
+        let solution: Solution<SimpleMove> = solve(&cube).unwrap();
+
+        let names: Vec<&str> = solution
+            .stages
+            .iter()
+            .map(|stage| &stage.name[..])
+            .collect();
+        let cmll = Set::CMLL
+            .built_in()
+            .iter()
+            .map(|(name, _)| format!("CMLL {name}"))
+            .find(|name| *name == names[2])
+            .unwrap_or_else(|| panic!("{} isn't a CMLL case", names[2]));
+        assert_eq!(names, ["First block", "Second block", &cmll, "LSE"]);
+        assert_eq!(
+            cube.apply(&solution.moves()),
+            Cube::create_solved(),
+            "{solution}"
+        );
+
+        let layers = |stage: &Stage<SimpleMove>| -> String {
+            stage
+                .notation
+                .chars()
+                .filter(char::is_ascii_alphabetic)
+                .collect()
+        };
+        assert!(
+            layers(&solution.stages[1])
+                .chars()
+                .all(|layer| "RUMr".contains(layer))
+        );
+        assert!(
+            layers(&solution.stages[3])
+                .chars()
+                .all(|layer| "MU".contains(layer))
+        );
+    }
+}
@@ -0,0 +1,210 @@
+use rand::Rng;
+
+use crate::{
+    cube::{
+        Cube,
+        mask::Mask,
+        r#move::Move,
+        move_sequence::Sequence,
+        simple_move::{Side, SimpleMove},
+    },
+    method::{
+        algorithms::Set,
+        recognition::{oll, pll},
+    },
+    solver::{Search, thistlethwaite},
+};
+
+/// Something a training scramble has to leave on the cube.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Constraint {
+    /// The pieces the mask cares about solved, like the cross with
+    /// `Mask::cross_on` or all but the last layer with
+    /// `Mask::first_two_layers_on`.
+    Solved(Mask),
+    /// The first two layers solved on `D` and the OLL case with this name.
+    Oll(String),
+    /// The first two layers solved on `D`, the last layer oriented and the
+    /// PLL case with this name.
+    Pll(String),
+    /// This many edges that aren't oriented, the way ZZ counts them.
+    BadEdges(usize),
+    /// No cross on `side` shorter than `moves` moves.
+    CrossAtLeast { side: Side, moves: usize },
+}
+
+impl Constraint {
+    /// What every cube meeting the constraint has solved.
+    fn keeps(&self) -> Mask {
+        match self {
+            Constraint::Solved(mask) => mask.clone(),
+            Constraint::Oll(_) => Set::OLL.keeps(),
+            Constraint::Pll(_) => Set::PLL.keeps(),
+            Constraint::BadEdges(_) | Constraint::CrossAtLeast { .. } => Mask::none(),
+        }
+    }
+
+    /// Whether `cube`, which has what the constraint keeps solved, meets it.
+    fn allows(&self, cube: &Cube) -> bool {
+        match self {
+            Constraint::Solved(_) => true,
+            Constraint::Oll(name) => oll::<SimpleMove>(cube).is_some_and(|case| case.name == name),
+            Constraint::Pll(name) => pll::<SimpleMove>(cube).is_some_and(|case| case.name == name),
+            Constraint::BadEdges(count) => {
+                cube.edge_orientation()
+                    .iter()
+                    .filter(|&&flip| flip == 1)
+                    .count()
+                    == *count
+            }
+            Constraint::CrossAtLeast { side, moves } => {
+                moves.checked_sub(1).is_none_or(|shorter| {
+                    Search::<SimpleMove>::new()
+                        .goal(Mask::cross_on(*side))
+                        .max_depth(shorter)
+                        .solve(cube.clone())
+                        .is_none()
+                })
+            }
+        }
+    }
+}
+
+/// Random-state scrambles for practising a step: every cube meeting the
+/// constraints is as likely, and a solver turns it into moves.
+///
+/// Cubes are drawn with what the constraints keep solved, like the first two
+/// layers for an OLL case, and drawn again until they meet the rest.
+#[derive(Debug, Clone)]
+pub struct Scrambler {
+    constraints: Vec<Constraint>,
+    attempts: usize,
+}
+
+impl Scrambler {
+    pub fn new() -> Self {
+        Scrambler {
+            constraints: Vec::new(),
+            attempts: 10_000,
+        }
+    }
+
+    pub fn constraint(mut self, constraint: Constraint) -> Self {
+        self.constraints.push(constraint);
+        self
+    }
+
+    /// Give up after drawing `attempts` cubes that miss a constraint.
+    pub fn attempts(mut self, attempts: usize) -> Self {
+        self.attempts = attempts;
+        self
+    }
+
+    /// A random cube meeting every constraint, `None` if none was drawn in
+    /// time, as when they can't be met together.
+    pub fn cube<R: Rng + ?Sized>(&self, rng: &mut R) -> Option<Cube> {
+        let keeps = self
+            .constraints
+            .iter()
+            .fold(Mask::none(), |keeps, constraint| {
+                keeps.union(&constraint.keeps())
+            });
+        (0..self.attempts)
+            .map(|_| Cube::random(rng, &keeps))
+            .find(|cube| {
+                self.constraints
+                    .iter()
+                    .all(|constraint| constraint.allows(cube))
+            })
+    }
+
+    /// Moves turning the solved cube into a random cube meeting every
+    /// constraint: its solution with Thistlethwaite's algorithm undone, so
+    /// at most 45 moves.
+    pub fn scramble<T: Move, R: Rng + ?Sized>(&self, rng: &mut R) -> Option<Sequence<T>> {
+        let phases = thistlethwaite::solve::<T>(&self.cube(rng)?)?;
+        let solution = phases
+            .iter()
+            .fold(Sequence::new(), |solution, phase| solution.concat(phase));
+        Some(solution.simplify().invert())
+    }
+}
+
+impl Default for Scrambler {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rand::{SeedableRng, rngs::StdRng};
+
+    use super::*;
+
+    fn scrambled(scrambler: &Scrambler, rng: &mut StdRng) -> Cube {
+        let scramble: Sequence<SimpleMove> = scrambler.scramble(rng).unwrap();
+        Cube::create_solved().apply(&scramble)
+    }
+
+    #[test]
+    fn keeps_the_solved_pieces() {
+        let mut rng = StdRng::seed_from_u64(1);
+        let cross = Scrambler::new().constraint(Constraint::Solved(Mask::cross_on(Side::DOWN)));
+        let last_layer =
+            Scrambler::new().constraint(Constraint::Solved(Mask::first_two_layers_on(Side::DOWN)));
+        for _ in 0..5 {
+            let cube = scrambled(&cross, &mut rng);
+            assert!(Mask::cross_on(Side::DOWN).matches(&cube));
+            assert!(!Mask::all().matches(&cube));
+            let cube = scrambled(&last_layer, &mut rng);
+            assert!(Mask::first_two_layers_on(Side::DOWN).matches(&cube));
+        }
+    }
+
+    #[test]
+    fn draws_last_layer_cases() {
+        let mut rng = StdRng::seed_from_u64(2);
+        let scrambler = Scrambler::new().constraint(Constraint::Oll("27".to_string()));
+        let cube = scrambled(&scrambler, &mut rng);
+        assert_eq!(oll::<SimpleMove>(&cube).unwrap().name, "27");
+
+        let scrambler = Scrambler::new().constraint(Constraint::Pll("T".to_string()));
+        let cube = scrambled(&scrambler, &mut rng);
+        assert_eq!(pll::<SimpleMove>(&cube).unwrap().name, "T");
+
+        let impossible = Scrambler::new()
+            .constraint(Constraint::Pll("Q".to_string()))
+            .attempts(50);
+        assert!(impossible.cube(&mut rng).is_none());
+    }
+
+    #[test]
+    fn counts_bad_edges_and_cross_moves() {
+        let mut rng = StdRng::seed_from_u64(3);
+        let scrambler = Scrambler::new()
+            .constraint(Constraint::BadEdges(4))
+            .constraint(Constraint::CrossAtLeast {
+                side: Side::DOWN,
+                moves: 7,
+            });
+        for _ in 0..3 {
+            let cube = scrambler.cube(&mut rng).unwrap();
+            let bad = cube
+                .edge_orientation()
+                .iter()
+                .filter(|&&flip| flip == 1)
+                .count();
+            assert_eq!(bad, 4);
+            let cross = Search::<SimpleMove>::new()
+                .goal(Mask::cross_on(Side::DOWN))
+                .solve(cube)
+                .unwrap();
+            assert!(cross.len() >= 7, "{cross}");
+        }
+
+        let oriented = Scrambler::new().constraint(Constraint::Solved(Mask::edge_orientation()));
+        let cube = scrambled(&oriented, &mut rng);
+        assert_eq!(cube.edge_orientation(), &[0; 12]);
+    }
+}
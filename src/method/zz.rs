@@ -0,0 +1,139 @@
+use std::fmt::Display;
+
+use crate::{
+    cube::{Cube, mask::Mask, r#move::Move, move_sequence::Sequence, simple_move::Side},
+    method::{
+        Solution, Stage,
+        algorithms::Set,
+        cfop::{OLL, PLL},
+        last_layer_stage,
+    },
+    solver::{Search, subgroup::Subgroup},
+};
+
+/// The OLL cases left once every edge is oriented.
+pub const OCLL: [(&str, &str); 7] = [
+    ("H", OLL[20].1),
+    ("Pi", OLL[21].1),
+    ("U", OLL[22].1),
+    ("T", OLL[23].1),
+    ("L", OLL[24].1),
+    ("Antisune", OLL[25].1),
+    ("Sune", OLL[26].1),
+];
+
+/// The PLL cases left once the corners are solved.
+pub const EPLL: [(&str, &str); 4] = [
+    ("H", PLL[8].1),
+    ("Ua", PLL[17].1),
+    ("Ub", PLL[18].1),
+    ("Z", PLL[20].1),
+];
+
+/// Solves `cube` the way a ZZ solver would, held with yellow at the bottom and
+/// green in front: an optimal EOLine, the left and right F2L blocks in
+/// `<R, U, L>`, then COLL and EPLL from the built-in algorithms.
+pub fn solve<T: Move + Display>(cube: &Cube) -> Option<Solution<T>> {
+    let mut cube = cube.clone();
+    let mut stages = Vec::new();
+
+    let mut solved = Mask::eo_line();
+    let eo_line: Sequence<T> = Search::new().goal(solved.clone()).solve(cube.clone())?;
+    cube = cube.apply(&eo_line);
+    stages.push(Stage {
+        name: "EOLine".to_string(),
+        notation: eo_line.to_string(),
+        moves: eo_line,
+    });
+
+    // Oriented edges stay oriented without F and B, so neither block can
+    // undo the EOLine.
+    let three_gen: Subgroup<T> = Subgroup::of_sides(&[Side::RIGHT, Side::UP, Side::LEFT]);
+    for (name, block) in [
+        ("Left block", Mask::first_block()),
+        ("Right block", Mask::second_block()),
+    ] {
+        solved = solved.union(&block);
+        let moves: Sequence<T> = Search::new()
+            .subgroup(three_gen.clone())
+            .goal(solved.clone())
+            .solve(cube.clone())?;
+        cube = cube.apply(&moves);
+        stages.push(Stage {
+            name: name.to_string(),
+            notation: moves.to_string(),
+            moves,
+        });
+    }
+
+    let goal = Set::COLL.goal();
+    let case = last_layer_stage(
+        &cube,
+        Side::DOWN,
+        Set::COLL.built_in(),
+        |cube| goal.matches(cube),
+        true,
+    )?;
+    cube = cube.apply(&case.moves);
+    stages.push(Stage {
+        name: format!("COLL {}", case.name),
+        notation: case.algorithm,
+        moves: case.moves,
+    });
+
+    let solved = |cube: &Cube| Mask::all().matches(cube);
+    let case = last_layer_stage(&cube, Side::DOWN, &EPLL, solved, true)?;
+    stages.push(Stage {
+        name: format!("EPLL {}", case.name),
+        notation: case.algorithm,
+        moves: case.moves,
+    });
+    Some(Solution { stages })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cube::simple_move::SimpleMove;
+
+    #[test]
+    fn solves_in_stages() {
+        let scramble: Sequence<SimpleMove> =
+            "R U F' L2 D B R2 U' F L' D2 B' R F2 U2 L".parse().unwrap();
+        let cube = Cube::create_solved().apply(&scramble);
+        let solution: Solution<SimpleMove> = solve(&cube).unwrap();
+
+        let names: Vec<&str> = solution
+            .stages
+            .iter()
+            .map(|stage| &stage.name[..])
+            .collect();
+        assert_eq!(
+            names,
+            [
+                "EOLine",
+                "Left block",
+                "Right block",
+                "COLL Pi 5",
+                "EPLL Ua"
+            ]
+        );
+        assert_eq!(
+            cube.apply(&solution.moves()),
+            Cube::create_solved(),
+            "{solution}"
+        );
+
+        let eo_line = &solution.stages[0].moves;
+        assert!(Mask::eo_line().matches(&cube.apply(eo_line)));
+        for stage in &solution.stages[1..3] {
+            assert!(
+                stage
+                    .moves
+                    .moves()
+                    .iter()
+                    .all(|r#move| { [Side::RIGHT, Side::UP, Side::LEFT].contains(&r#move.side()) })
+            );
+        }
+    }
+}
@@ -1,44 +1,437 @@
-use crate::cube::{Cube, r#move::Move, move_sequence::Sequence};
+pub mod cost;
+pub mod pruning;
+pub mod statistics;
+pub mod subgroup;
+pub mod thistlethwaite;
+
+use std::{collections::VecDeque, fmt::Display, sync::Arc};
+
+use crate::cube::{
+    Cube, mask::Mask, metric::Metric, r#move::Move, move_sequence::Sequence, notation::HOME,
+    simple_move::Side,
+};
+use crate::solver::{
+    cost::CostModel,
+    pruning::Heuristic,
+    subgroup::{Generator, Subgroup},
+};
+use crate::table::TableCache;
 
 pub fn solve<T: Move>(cube: Cube) -> Option<Sequence<T>> {
-    println!("trying to solve the cube {cube:?}");
-
-    let solved = Cube::create_solved();
-    let mut iteration = vec![Snapshot {
-        cube: cube.clone(),
-        sequence: Sequence::new(),
-    }];
-
-    if cube == solved {
-        return Some(Sequence::new());
-    }
-
-    let iterations = 10;
-    for i in 1..=iterations {
-        let mut next_iter = Vec::new();
-
-        let size = std::mem::size_of_val(&*iteration);
-        println!("Searching {} states, move {} ({} bytes)", iteration.len(), i, size);
-
-        for snapshot in &iteration {
-            for r#move in T::all_moves() {
-            let state = Snapshot {
-                    cube: snapshot.cube.apply_move(&r#move),
-                    sequence: snapshot.sequence.apply(&r#move),
-                };
-                if state.cube == solved {
-                    return Some(state.sequence);
-                }
-                next_iter.push(state);
+    Search::new().solve(cube)
+}
+
+/// Iterative deepening search over the moves of a subgroup, finding the
+/// cheapest ways, in the chosen metric or cost model, to reach a goal. The
+/// goal is the solved cube unless a mask is given, and branches are cut off
+/// using the pattern databases of `Heuristic`.
+///
+/// Only simplified sequences are generated: a layer is never turned twice in a
+/// row and never turned again right after another layer on its axis, since
+/// both would merge into a shorter sequence. That only holds when the
+/// subgroup has the merged move, so a layer given as just `U'` is repeated
+/// for the other turns. Slice and wide moves come back
+/// as the face turns they expand to, `solve_written` also gives them as
+/// written.
+#[derive(Debug, Clone)]
+pub struct Search<T: Move> {
+    goal: Mask,
+    subgroup: Subgroup<T>,
+    cost: Arc<dyn CostModel<T>>,
+    max_depth: Option<usize>,
+    extra: usize,
+    commuting: bool,
+    tables: Option<TableCache>,
+}
+
+impl<T: Move> Search<T> {
+    pub fn new() -> Self {
+        Search {
+            goal: Mask::all(),
+            subgroup: Subgroup::all(),
+            cost: Arc::new(Metric::HTM),
+            max_depth: None,
+            extra: 0,
+            commuting: false,
+            tables: None,
+        }
+    }
+
+    /// Only solve the pieces `goal` cares about.
+    pub fn goal(mut self, goal: Mask) -> Self {
+        self.goal = goal;
+        self
+    }
+
+    /// Only use the moves of `subgroup`.
+    pub fn subgroup(mut self, subgroup: Subgroup<T>) -> Self {
+        self.subgroup = subgroup;
+        self
+    }
+
+    /// Count solution lengths in `metric`, HTM by default.
+    pub fn metric(self, metric: Metric) -> Self {
+        self.cost_model(metric)
+    }
+
+    /// Minimise `cost` instead of a metric. When it charges differently for
+    /// the two orders of commuting moves, enable `commuting` so both orders
+    /// are considered.
+    pub fn cost_model(mut self, cost: impl CostModel<T> + 'static) -> Self {
+        self.cost = Arc::new(cost);
+        self
+    }
+
+    /// Give up once no solution up to `max_depth` exists, counted in the
+    /// metric or cost model. By default that's 20 of its most expensive moves.
+    pub fn max_depth(mut self, max_depth: usize) -> Self {
+        self.max_depth = Some(max_depth);
+        self
+    }
+
+    /// The most a solution may cost, set with `max_depth` or the default.
+    pub fn depth_limit(&self) -> usize {
+        self.max_depth
+            .unwrap_or_else(|| 20 * self.cost.most_expensive())
+    }
+
+    /// Also yield solutions up to `extra` moves longer than the optimal one.
+    pub fn extra(mut self, extra: usize) -> Self {
+        self.extra = extra;
+        self
+    }
+
+    /// Whether sequences that only differ in the order of commuting opposite
+    /// side turns, like `R L` and `L R`, are all yielded. Off by default.
+    pub fn commuting(mut self, commuting: bool) -> Self {
+        self.commuting = commuting;
+        self
+    }
+
+    /// Store the pruning tables in `cache` so later runs can reuse them.
+    pub fn tables(mut self, cache: TableCache) -> Self {
+        self.tables = Some(cache);
+        self
+    }
+
+    /// What `moves` cost in the search's metric or cost model, taking them as
+    /// face turns.
+    pub fn cost(&self, moves: &Sequence<T>) -> usize {
+        self.cost.sequence_cost(moves.moves())
+    }
+
+    pub fn solve(&self, cube: Cube) -> Option<Sequence<T>> {
+        self.solutions(cube).next()
+    }
+
+    /// Yields nothing right away when the goal can't be reached in the
+    /// search's subgroup.
+    pub fn solutions(&self, cube: Cube) -> Solutions<T> {
+        let heuristic =
+            Heuristic::from_turned(&self.goal, &self.subgroup.turned(), self.tables.as_ref());
+        let solvable = heuristic.estimate(&cube).is_some()
+            && (!self.goal.is_full() || self.subgroup.might_solve(&cube));
+        let costs = self
+            .subgroup
+            .generators()
+            .iter()
+            .map(|generator| {
+                generator
+                    .name
+                    .as_ref()
+                    .map(|name| self.cost.written_cost(name, &generator.expanded))
+            })
+            .collect();
+        Solutions {
+            search: self.clone(),
+            heuristic,
+            costs,
+            cube,
+            depth: solvable.then_some(0),
+            optimal: None,
+            found: VecDeque::new(),
+        }
+    }
+
+    fn allowed(&self, path: &[usize], next: &Generator<T>) -> bool {
+        let generators = self.subgroup.generators();
+        let Some(previous) = path.last().map(|&index| &generators[index]) else {
+            return true;
+        };
+        if previous.layer == next.layer {
+            return !next.closed;
+        }
+        if previous.axis != next.axis {
+            return true;
+        }
+        if !self.commuting {
+            return previous.layer < next.layer;
+        }
+        match path
+            .len()
+            .checked_sub(2)
+            .map(|index| &generators[path[index]])
+        {
+            Some(before) => before.layer != next.layer || !next.closed,
+            None => true,
+        }
+    }
+
+    /// Collects the solutions costing exactly `depth` and returns the
+    /// cheapest cost above it that was cut off, which is the next depth worth
+    /// searching. `costs` holds what each slice or wide move costs.
+    #[allow(clippy::too_many_arguments)]
+    fn search(
+        &self,
+        heuristic: &Heuristic,
+        costs: &[Option<usize>],
+        (cube, frame): (&Cube, [Side; 6]),
+        depth: usize,
+        spent: usize,
+        path: &mut Vec<usize>,
+        found: &mut VecDeque<Vec<usize>>,
+    ) -> Option<usize> {
+        let bound = spent + heuristic.estimate(cube)? * self.cost.cheapest();
+        if bound > depth {
+            return Some(bound);
+        }
+        if spent == depth {
+            if self.goal.matches(cube) && frame == HOME {
+                found.push_back(path.clone());
+            }
+            return Some(depth + self.cost.cheapest());
+        }
+        let generators = self.subgroup.generators();
+        let mut next_depth: Option<usize> = None;
+        let previous = path.last().map(|&index| &generators[index].hand);
+        for (index, generator) in generators.iter().enumerate() {
+            if !self.allowed(path, generator) {
+                continue;
             }
+            let cost = match &generator.face {
+                Some(face) => self.cost.cost(previous, face),
+                None => costs[index].unwrap_or_default(),
+            };
+            let total = spent + cost;
+            let cut_off = if total > depth {
+                Some(total)
+            } else {
+                path.push(index);
+                let cut_off = self.search(
+                    heuristic,
+                    costs,
+                    (
+                        &cube.compose(&generator.turned),
+                        generator.rotation.map(|side| frame[side as usize]),
+                    ),
+                    depth,
+                    total,
+                    path,
+                    found,
+                );
+                path.pop();
+                cut_off
+            };
+            next_depth = match (next_depth, cut_off) {
+                (Some(a), Some(b)) => Some(a.min(b)),
+                (a, b) => a.or(b),
+            };
         }
-        iteration = next_iter
+        next_depth
+    }
+}
+
+impl<T: Move + Display> Search<T> {
+    /// The cheapest solution as it's written, with the subgroup's slice and
+    /// wide moves, along with the face turns it expands to.
+    pub fn solve_written(&self, cube: Cube) -> Option<(String, Sequence<T>)> {
+        let mut solutions = self.solutions(cube);
+        let path = solutions.next_path()?;
+        Some((self.subgroup.written(&path), self.subgroup.sequence(&path)))
     }
+}
 
-    None
+impl<T: Move> Default for Search<T> {
+    fn default() -> Self {
+        Self::new()
+    }
 }
 
-struct Snapshot<T: Move> {
+/// Every solution of a cube, shortest first. Each length is searched in full
+/// the first time one of its solutions is requested.
+pub struct Solutions<T: Move> {
+    search: Search<T>,
+    heuristic: Heuristic,
+    costs: Vec<Option<usize>>,
     cube: Cube,
-    sequence: Sequence<T>,
+    depth: Option<usize>,
+    optimal: Option<usize>,
+    found: VecDeque<Vec<usize>>,
+}
+
+impl<T: Move> Solutions<T> {
+    /// The next solution as indices into the subgroup's moves.
+    fn next_path(&mut self) -> Option<Vec<usize>> {
+        while self.found.is_empty() {
+            let last_depth = match self.optimal {
+                Some(optimal) => optimal + self.search.extra,
+                None => self.search.depth_limit(),
+            };
+            let depth = self.depth.filter(|depth| *depth <= last_depth)?;
+            self.depth = self.search.search(
+                &self.heuristic,
+                &self.costs,
+                (&self.cube, HOME),
+                depth,
+                0,
+                &mut Vec::new(),
+                &mut self.found,
+            );
+            if !self.found.is_empty() && self.optimal.is_none() {
+                self.optimal = Some(depth);
+            }
+        }
+        self.found.pop_front()
+    }
+}
+
+impl<T: Move> Iterator for Solutions<T> {
+    type Item = Sequence<T>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let path = self.next_path()?;
+        Some(self.search.subgroup.sequence(&path))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashSet;
+
+    use super::*;
+    use crate::cube::{mask::Care, scrambled, simple_move::SimpleMove};
+
+    #[test]
+    fn solves_short_scramble() {
+        let cube = scrambled("R U F'");
+        let solution: Sequence<SimpleMove> = solve(cube.clone()).unwrap();
+        assert_eq!(solution.to_string(), "F U' R'");
+    }
+
+    #[test]
+    fn solved_cube_needs_no_moves() {
+        let solutions: Vec<Sequence<SimpleMove>> =
+            Search::new().solutions(Cube::create_solved()).collect();
+        assert_eq!(solutions, vec![Sequence::new()]);
+    }
+
+    #[test]
+    fn commuting_variants_are_optional() {
+        let cube = scrambled("R L U");
+        let deduplicated: Vec<Sequence<SimpleMove>> =
+            Search::new().solutions(cube.clone()).collect();
+        assert_eq!(deduplicated.len(), 1);
+
+        let all: Vec<String> = Search::<SimpleMove>::new()
+            .commuting(true)
+            .solutions(cube)
+            .map(|solution| solution.to_string())
+            .collect();
+        assert_eq!(all, vec!["U' R' L'", "U' L' R'"]);
+    }
+
+    #[test]
+    fn extra_moves_stay_within_bound() {
+        let cube = scrambled("R U");
+        let solutions: Vec<Sequence<SimpleMove>> =
+            Search::new().extra(2).solutions(cube.clone()).collect();
+
+        assert_eq!(solutions[0].to_string(), "U' R'");
+        assert!(solutions.iter().all(|solution| solution.len() <= 4));
+        for solution in &solutions {
+            assert_eq!(cube.apply(solution), Cube::create_solved(), "{solution}");
+        }
+        let distinct: HashSet<&Sequence<SimpleMove>> = solutions.iter().collect();
+        assert_eq!(distinct.len(), solutions.len());
+    }
+
+    #[test]
+    fn counts_lengths_in_metric() {
+        let sequence: Sequence<SimpleMove> = "R U2 R' U' F2".parse().unwrap();
+        assert_eq!(sequence.length(Metric::HTM), 5);
+        assert_eq!(sequence.length(Metric::QTM), 7);
+        assert_eq!(sequence.length(Metric::STM), 5);
+    }
+
+    #[test]
+    fn solves_optimally_in_quarter_turns() {
+        let cube = scrambled("R2 U");
+        let solutions: Vec<Sequence<SimpleMove>> = Search::new()
+            .metric(Metric::QTM)
+            .extra(2)
+            .solutions(cube.clone())
+            .collect();
+        assert_eq!(solutions[0].to_string(), "U' R2");
+        // Every quarter turn changes the permutation parity, so QTM solutions
+        // of one state all have even or all have odd length.
+        assert!(
+            solutions
+                .iter()
+                .all(|solution| [3, 5].contains(&solution.length(Metric::QTM)))
+        );
+    }
+
+    #[test]
+    fn solves_masked_goals() {
+        let cube = scrambled("R U F' L2 D B R2 U' F L' D2 B' R");
+
+        let cross: Sequence<SimpleMove> = Search::new()
+            .goal(Mask::cross())
+            .solve(cube.clone())
+            .unwrap();
+        assert!(Mask::cross().matches(&cube.apply(&cross)));
+        assert!(cross.len() <= 8);
+
+        let edges = Search::<SimpleMove>::new()
+            .goal(Mask::edge_orientation())
+            .solve(cube.clone())
+            .unwrap();
+        assert!(Mask::edge_orientation().matches(&cube.apply(&edges)));
+        assert!(edges.len() <= 7);
+    }
+
+    #[test]
+    fn masked_goal_respects_subgroup() {
+        let cube = scrambled("F");
+        let two_gen: Subgroup<SimpleMove> = "<R,U>".parse().unwrap();
+        let search = Search::new().subgroup(two_gen);
+        assert!(
+            search
+                .clone()
+                .goal(Mask::edge_orientation())
+                .solve(cube.clone())
+                .is_none()
+        );
+        assert!(
+            search
+                .goal(Mask::cross().with_edges(&[0], Care::Ignore))
+                .solve(cube)
+                .is_some()
+        );
+    }
+
+    #[test]
+    fn estimates_without_every_inverse() {
+        // Only `U'` is given, so `U` is three moves away, not one.
+        let search = Search::<SimpleMove>::new().subgroup("<R,U'>".parse().unwrap());
+        let solution = search.solve(scrambled("U R")).unwrap();
+        assert_eq!(solution.to_string(), "R' U'");
+
+        let solution = search
+            .clone()
+            .goal(Mask::cross())
+            .solve(scrambled("U R'"))
+            .unwrap();
+        assert_eq!(solution.to_string(), "R U'");
+    }
 }
@@ -0,0 +1,243 @@
+use std::{collections::HashMap, fmt::Debug};
+
+use crate::cube::{
+    metric::Metric,
+    r#move::Move,
+    simple_move::{Side, Turn},
+};
+
+/// What a search minimises. The cost of a move may depend on the move before
+/// it, which is `None` for the first move of a sequence. Every move must cost
+/// at least one.
+pub trait CostModel<T: Move>: Debug {
+    fn cost(&self, previous: Option<&T>, next: &T) -> usize;
+
+    /// A lower bound on the cost of any move.
+    fn cheapest(&self) -> usize {
+        1
+    }
+
+    /// An upper bound on the cost of a face turn, which scales the default
+    /// limit of a search.
+    fn most_expensive(&self) -> usize {
+        self.cheapest()
+    }
+
+    fn sequence_cost(&self, moves: &[T]) -> usize {
+        let mut previous = None;
+        let mut total = 0;
+        for r#move in moves {
+            total += self.cost(previous, r#move);
+            previous = Some(r#move);
+        }
+        total
+    }
+
+    /// The cost of a slice or wide move written as `name`, which `expanded`
+    /// gives as face turns. Those face turns by default.
+    fn written_cost(&self, _name: &str, expanded: &[T]) -> usize {
+        self.sequence_cost(expanded)
+    }
+}
+
+impl<T: Move> CostModel<T> for Metric {
+    fn cost(&self, _previous: Option<&T>, next: &T) -> usize {
+        Metric::cost(self, next)
+    }
+
+    fn written_cost(&self, name: &str, _expanded: &[T]) -> usize {
+        self.count(name)
+    }
+
+    fn most_expensive(&self) -> usize {
+        match self {
+            Metric::QTM => 2,
+            _ => 1,
+        }
+    }
+}
+
+/// Execution cost of moves, for finding solutions that are fast to perform
+/// rather than short. Costs are in tenths of an easy move.
+///
+/// Every turn has its own cost and turning one side after another can add a
+/// penalty, which is how regrips are modelled.
+#[derive(Debug, Clone)]
+pub struct Ergonomics {
+    moves: HashMap<(Side, Turn), usize>,
+    transitions: HashMap<(Side, Side), usize>,
+}
+
+impl Ergonomics {
+    /// Every turn costs one move and there are no penalties.
+    pub fn new() -> Self {
+        let mut moves = HashMap::new();
+        for side in SIDES {
+            for turns in [Turn::RIGHT, Turn::LEFT, Turn::TWO] {
+                moves.insert((side, turns), 10);
+            }
+        }
+        Ergonomics {
+            moves,
+            transitions: HashMap::new(),
+        }
+    }
+
+    /// Costs for two-handed speedsolving, where `R` and `U` are fastest, `B`
+    /// and `D'` slow, and switching between `F`/`B` and `R`/`L` turns needs a
+    /// regrip.
+    pub fn speedsolving() -> Self {
+        let costs = [
+            (Side::UP, [8, 8, 12]),
+            (Side::RIGHT, [8, 8, 12]),
+            (Side::LEFT, [10, 10, 15]),
+            (Side::FRONT, [12, 14, 18]),
+            (Side::DOWN, [13, 15, 20]),
+            (Side::BACK, [25, 25, 30]),
+        ];
+        let mut ergonomics = Ergonomics::new();
+        for (side, [right, left, two]) in costs {
+            ergonomics = ergonomics
+                .with_move(side, Turn::RIGHT, right)
+                .with_move(side, Turn::LEFT, left)
+                .with_move(side, Turn::TWO, two);
+        }
+        for (from, to) in [
+            (Side::FRONT, Side::RIGHT),
+            (Side::FRONT, Side::LEFT),
+            (Side::BACK, Side::RIGHT),
+            (Side::BACK, Side::LEFT),
+            (Side::RIGHT, Side::FRONT),
+            (Side::RIGHT, Side::BACK),
+            (Side::LEFT, Side::FRONT),
+            (Side::LEFT, Side::BACK),
+        ] {
+            ergonomics = ergonomics.with_transition(from, to, 5);
+        }
+        ergonomics
+    }
+
+    pub fn with_move(mut self, side: Side, turns: Turn, cost: usize) -> Self {
+        assert!(cost > 0, "every move must cost something");
+        self.moves.insert((side, turns), cost);
+        self
+    }
+
+    /// Adds `penalty` whenever `to` is turned right after `from`.
+    pub fn with_transition(mut self, from: Side, to: Side, penalty: usize) -> Self {
+        self.transitions.insert((from, to), penalty);
+        self
+    }
+}
+
+impl Default for Ergonomics {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T: Move> CostModel<T> for Ergonomics {
+    fn cost(&self, previous: Option<&T>, next: &T) -> usize {
+        let penalty = previous
+            .and_then(|previous| self.transitions.get(&(previous.side(), next.side())))
+            .unwrap_or(&0);
+        self.moves[&(next.side(), next.turns())] + penalty
+    }
+
+    fn cheapest(&self) -> usize {
+        self.moves.values().copied().min().unwrap_or(1)
+    }
+
+    fn most_expensive(&self) -> usize {
+        let penalty = self.transitions.values().copied().max().unwrap_or(0);
+        self.moves.values().copied().max().unwrap_or(1) + penalty
+    }
+}
+
+const SIDES: [Side; 6] = [
+    Side::UP,
+    Side::DOWN,
+    Side::FRONT,
+    Side::BACK,
+    Side::RIGHT,
+    Side::LEFT,
+];
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        cube::{Cube, move_sequence::Sequence, scrambled, simple_move::SimpleMove},
+        solver::Search,
+    };
+
+    #[test]
+    fn adds_transition_penalties() {
+        let ergonomics = Ergonomics::speedsolving();
+        let sequence: Sequence<SimpleMove> = "R U F R".parse().unwrap();
+        assert_eq!(
+            ergonomics.sequence_cost(sequence.moves()),
+            8 + 8 + 12 + 5 + 8
+        );
+        assert_eq!(Metric::QTM.sequence_cost(sequence.moves()), 4);
+    }
+
+    #[test]
+    fn default_limit_scales_with_costs() {
+        let scramble: Sequence<SimpleMove> = "R U F".parse().unwrap();
+        let cube = Cube::create_solved().apply(&scramble);
+        for ergonomics in [Ergonomics::new(), Ergonomics::speedsolving()] {
+            let search = Search::<SimpleMove>::new().cost_model(ergonomics);
+            let solution = search.solve(cube.clone()).unwrap();
+            assert_eq!(solution.to_string(), "F' U' R'");
+        }
+        assert_eq!(
+            Search::<SimpleMove>::new()
+                .cost_model(Ergonomics::speedsolving())
+                .depth_limit(),
+            20 * 35
+        );
+        assert_eq!(Search::<SimpleMove>::new().depth_limit(), 20);
+    }
+
+    #[test]
+    fn minimises_execution_cost() {
+        let scramble: Sequence<SimpleMove> = "R L".parse().unwrap();
+        let cube = Cube::create_solved().apply(&scramble);
+        let ergonomics = Ergonomics::new().with_transition(Side::RIGHT, Side::LEFT, 20);
+
+        let solution = Search::<SimpleMove>::new()
+            .commuting(true)
+            .solve(cube.clone())
+            .unwrap();
+        assert_eq!(solution.to_string(), "R' L'");
+
+        let solution = Search::<SimpleMove>::new()
+            .cost_model(ergonomics)
+            .commuting(true)
+            .solve(cube)
+            .unwrap();
+        assert_eq!(solution.to_string(), "L' R'");
+
+        let speedsolving = Ergonomics::speedsolving();
+        let cost = |moves: &str| {
+            let moves: Sequence<SimpleMove> = moves.parse().unwrap();
+            speedsolving.sequence_cost(moves.moves())
+        };
+        assert!(cost("D'") > cost("D"));
+        assert!(cost("B") > cost("F"));
+        assert!(cost("R") < cost("F"));
+    }
+
+    #[test]
+    fn penalises_regrips_after_wide_moves() {
+        let cube = scrambled("F' r U r'");
+        let ergonomics = Ergonomics::new().with_transition(Side::RIGHT, Side::FRONT, 30);
+        let search = Search::<SimpleMove>::new()
+            .subgroup("<r,U,F>".parse().unwrap())
+            .cost_model(ergonomics);
+        let (written, _) = search.solve_written(cube.clone()).unwrap();
+        assert_eq!(written, "r U' r' F");
+        assert!(search.max_depth(69).solve(cube).is_none());
+    }
+}
@@ -0,0 +1,239 @@
+use std::{
+    collections::HashMap,
+    sync::{Arc, LazyLock, Mutex},
+};
+
+use crate::{
+    cube::{
+        Cube,
+        mask::{Care, Mask},
+        r#move::Move,
+    },
+    table::{Table, TableCache, checksum},
+};
+
+const VERSION: u32 = 2;
+const GROUP_SIZE: usize = 4;
+const UNREACHABLE: u8 = u8::MAX;
+
+static TABLES: LazyLock<Mutex<HashMap<String, Arc<PruningTable>>>> =
+    LazyLock::new(|| Mutex::new(HashMap::new()));
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Piece {
+    Corner(usize),
+    Edge(usize),
+}
+
+/// Lower bound on the number of moves needed to reach a mask, taken as the
+/// largest exact distance of a few small groups of its pieces.
+///
+/// The pieces are split into groups of up to four, edges first, and each group
+/// gets a pattern database found by a breadth-first search from every state in
+/// which the group matches the mask. A piece's state is its position and
+/// orientation, one of 24 for both corners and edges, so a group's table has
+/// `24^n` entries. Tables are shared between searches with the same pieces
+/// and moves, and written to `cache` when one is given.
+#[derive(Debug, Clone)]
+pub struct Heuristic {
+    tables: Vec<Arc<PruningTable>>,
+}
+
+impl Heuristic {
+    pub fn new<T: Move>(mask: &Mask, moves: &[T], cache: Option<&TableCache>) -> Self {
+        let turned: Vec<Cube> = moves
+            .iter()
+            .map(|r#move| Cube::create_solved().apply_move(r#move))
+            .collect();
+        Heuristic::from_turned(mask, &turned, cache)
+    }
+
+    /// Same as `new` for moves that aren't face turns, each given as the cube
+    /// it turns the solved cube into.
+    pub fn from_turned(mask: &Mask, turned: &[Cube], cache: Option<&TableCache>) -> Self {
+        let pieces: Vec<(Piece, Care)> = (0..12)
+            .map(|edge| (Piece::Edge(edge), mask.edge(edge)))
+            .chain((0..8).map(|corner| (Piece::Corner(corner), mask.corner(corner))))
+            .filter(|(_, care)| *care != Care::Ignore)
+            .collect();
+        // The search from the goal goes backwards, so it needs each move's
+        // inverse, which the moves themselves may not include.
+        let transitions: Vec<Transitions> = turned
+            .iter()
+            .map(|turned| Transitions::new(&turned.inverse()))
+            .collect();
+        let moves_key = format!("{turned:?}");
+
+        let tables = pieces
+            .chunks(GROUP_SIZE)
+            .map(|group| PruningTable::load(group, &transitions, &moves_key, cache))
+            .collect();
+        Heuristic { tables }
+    }
+
+    /// `None` when the moves can't reach the mask at all.
+    pub fn estimate(&self, cube: &Cube) -> Option<usize> {
+        let mut corners = [0u8; 8];
+        for (position, &piece) in cube.corner_permutation().iter().enumerate() {
+            corners[piece as usize] = position as u8 * 3 + cube.corner_orientation()[position];
+        }
+        let mut edges = [0u8; 12];
+        for (position, &piece) in cube.edge_permutation().iter().enumerate() {
+            edges[piece as usize] = position as u8 * 2 + cube.edge_orientation()[position];
+        }
+
+        let mut estimate = 0;
+        for table in &self.tables {
+            let index = table.pieces.iter().rev().fold(0, |index, (piece, _)| {
+                let state = match piece {
+                    Piece::Corner(corner) => corners[*corner],
+                    Piece::Edge(edge) => edges[*edge],
+                };
+                index * 24 + state as usize
+            });
+            match table.data[index] {
+                UNREACHABLE => return None,
+                distance => estimate = estimate.max(distance as usize),
+            }
+        }
+        Some(estimate)
+    }
+}
+
+/// Where each corner and edge state goes under one move.
+struct Transitions {
+    corners: [u8; 24],
+    edges: [u8; 24],
+}
+
+impl Transitions {
+    fn new(turned: &Cube) -> Self {
+        let mut corners = [0; 24];
+        for position in 0..8 {
+            let from = turned.corner_permutation()[position];
+            let twist = turned.corner_orientation()[position];
+            for orientation in 0..3 {
+                corners[(from * 3 + orientation) as usize] =
+                    position as u8 * 3 + (orientation + twist) % 3;
+            }
+        }
+        let mut edges = [0; 24];
+        for position in 0..12 {
+            let from = turned.edge_permutation()[position];
+            let flip = turned.edge_orientation()[position];
+            for orientation in 0..2 {
+                edges[(from * 2 + orientation) as usize] =
+                    position as u8 * 2 + (orientation ^ flip);
+            }
+        }
+        Transitions { corners, edges }
+    }
+}
+
+#[derive(Debug)]
+struct PruningTable {
+    pieces: Vec<(Piece, Care)>,
+    data: Table,
+}
+
+impl PruningTable {
+    fn load(
+        pieces: &[(Piece, Care)],
+        transitions: &[Transitions],
+        moves_key: &str,
+        cache: Option<&TableCache>,
+    ) -> Arc<Self> {
+        let key = format!("{pieces:?} {moves_key}");
+        let mut tables = TABLES.lock().unwrap();
+        if let Some(table) = tables.get(&key) {
+            return table.clone();
+        }
+
+        let generate = || generate(pieces, transitions);
+        let data = match cache {
+            Some(cache) => {
+                let name = format!("pruning-{:016x}", checksum(key.as_bytes()));
+                cache.load(&name, VERSION, generate)
+            }
+            None => Table::Generated(generate()),
+        };
+        let table = Arc::new(PruningTable {
+            pieces: pieces.to_vec(),
+            data,
+        });
+        tables.insert(key, table.clone());
+        table
+    }
+}
+
+fn generate(pieces: &[(Piece, Care)], transitions: &[Transitions]) -> Vec<u8> {
+    let size = 24usize.pow(pieces.len() as u32);
+    let mut distances = vec![UNREACHABLE; size];
+    let mut frontier: Vec<usize> = (0..size)
+        .filter(|&index| is_goal(pieces, &decode(index)))
+        .collect();
+    for &index in &frontier {
+        distances[index] = 0;
+    }
+
+    let mut distance = 0;
+    while !frontier.is_empty() {
+        distance += 1;
+        let mut next = Vec::new();
+        for index in frontier {
+            let states = decode(index);
+            for transition in transitions {
+                let moved =
+                    states
+                        .iter()
+                        .zip(pieces)
+                        .rev()
+                        .fold(0, |moved, (&state, (piece, _))| {
+                            let state = match piece {
+                                Piece::Corner(_) => transition.corners[state as usize],
+                                Piece::Edge(_) => transition.edges[state as usize],
+                            };
+                            moved * 24 + state as usize
+                        });
+                if distances[moved] == UNREACHABLE {
+                    distances[moved] = distance;
+                    next.push(moved);
+                }
+            }
+        }
+        frontier = next;
+    }
+    distances
+}
+
+fn decode(mut index: usize) -> [u8; GROUP_SIZE] {
+    std::array::from_fn(|_| {
+        let state = index % 24;
+        index /= 24;
+        state as u8
+    })
+}
+
+fn is_goal(pieces: &[(Piece, Care)], states: &[u8]) -> bool {
+    let mut corner_positions = [false; 8];
+    let mut edge_positions = [false; 12];
+    for ((piece, care), &state) in pieces.iter().zip(states) {
+        let (home, position, orientation, positions) = match piece {
+            Piece::Corner(corner) => (*corner, state / 3, state % 3, &mut corner_positions[..]),
+            Piece::Edge(edge) => (*edge, state / 2, state % 2, &mut edge_positions[..]),
+        };
+        if positions[position as usize] {
+            return false;
+        }
+        positions[position as usize] = true;
+        let solved = match care {
+            Care::Ignore => true,
+            Care::Orientation => orientation == 0,
+            Care::Full => orientation == 0 && position as usize == home,
+        };
+        if !solved {
+            return false;
+        }
+    }
+    true
+}
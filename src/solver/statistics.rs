@@ -0,0 +1,167 @@
+use std::{collections::HashSet, fmt::Display, hash::Hash};
+
+use crate::{
+    cube::{Cube, r#move::Move},
+    solver::subgroup::Subgroup,
+};
+
+/// How many states of a space are at each distance from the solved one, like
+/// the tables behind God's number.
+///
+/// A space is the states the moves of a subgroup reach, told apart by a
+/// coordinate such as the corners alone or the edge orientation. Moves have
+/// to act on the coordinate: two cubes with the same coordinate must still
+/// have the same one after any move, which holds when the coordinate is made
+/// of whole pieces or orientations. Distances count one per move, so HTM for
+/// the usual subgroups and QTM when only quarter turns are given.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Distribution {
+    counts: Vec<usize>,
+    complete: bool,
+}
+
+impl Distribution {
+    /// Explores the space breadth first, one distance at a time, stopping
+    /// after `max_depth` or once every state has been found.
+    ///
+    /// Every state found so far is kept, so the whole cube or the corners
+    /// alone need a `max_depth`, while spaces up to a few million states can
+    /// be explored fully.
+    pub fn explore<T: Move, K: Hash + Eq>(
+        subgroup: &Subgroup<T>,
+        coordinate: impl Fn(&Cube) -> K,
+        max_depth: usize,
+    ) -> Self {
+        let solved = Cube::create_solved();
+        let mut seen = HashSet::from([coordinate(&solved)]);
+        let mut frontier = vec![solved];
+        let mut counts = vec![1];
+        let turned = subgroup.turned();
+        while counts.len() <= max_depth {
+            let mut next = Vec::new();
+            for cube in &frontier {
+                for turned in &turned {
+                    let moved = cube.compose(turned);
+                    if seen.insert(coordinate(&moved)) {
+                        next.push(moved);
+                    }
+                }
+            }
+            if next.is_empty() {
+                return Distribution {
+                    counts,
+                    complete: true,
+                };
+            }
+            counts.push(next.len());
+            frontier = next;
+        }
+        Distribution {
+            counts,
+            complete: false,
+        }
+    }
+
+    /// The number of states at each distance, starting with the solved one.
+    pub fn counts(&self) -> &[usize] {
+        &self.counts
+    }
+
+    /// Whether every state was found, so the counts cover the whole space.
+    pub fn is_complete(&self) -> bool {
+        self.complete
+    }
+
+    pub fn states(&self) -> usize {
+        self.counts.iter().sum()
+    }
+
+    /// The largest distance found, the space's God's number when complete.
+    pub fn diameter(&self) -> usize {
+        self.counts.len() - 1
+    }
+
+    /// The mean distance of the states found.
+    pub fn average(&self) -> f64 {
+        let total: usize = self
+            .counts
+            .iter()
+            .enumerate()
+            .map(|(distance, count)| distance * count)
+            .sum();
+        total as f64 / self.states() as f64
+    }
+}
+
+impl Display for Distribution {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let width = self.states().to_string().len();
+        for (distance, count) in self.counts.iter().enumerate() {
+            writeln!(f, "{distance:>8} {count:>width$}")?;
+        }
+        let total = if self.complete { "total" } else { "so far" };
+        write!(f, "{total:>8} {:>width$}", self.states())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cube::simple_move::{ALL_MOVES, SimpleMove, Turn};
+
+    fn subgroup(generators: &str) -> Subgroup<SimpleMove> {
+        generators.parse().unwrap()
+    }
+
+    #[test]
+    fn counts_the_start_of_the_whole_cube() {
+        let distribution = Distribution::explore(&Subgroup::<SimpleMove>::all(), Cube::clone, 3);
+        assert_eq!(distribution.counts(), [1, 18, 243, 3240]);
+        assert!(!distribution.is_complete());
+
+        let quarter_turns = Subgroup::from_moves(
+            Vec::new(),
+            ALL_MOVES
+                .into_iter()
+                .filter(|r#move| r#move.turns != Turn::TWO)
+                .collect(),
+        );
+        let distribution = Distribution::explore(&quarter_turns, Cube::clone, 2);
+        assert_eq!(distribution.counts(), [1, 12, 114]);
+    }
+
+    #[test]
+    fn explores_small_spaces_completely() {
+        let distribution = Distribution::explore(&subgroup("<U>"), Cube::clone, 20);
+        assert_eq!(distribution.counts(), [1, 3]);
+        assert!(distribution.is_complete());
+        assert_eq!(
+            distribution.to_string(),
+            "       0 1\n       1 3\n   total 4"
+        );
+
+        let orientation = |cube: &Cube| *cube.edge_orientation();
+        let distribution = Distribution::explore(&Subgroup::<SimpleMove>::all(), orientation, 20);
+        assert_eq!(distribution.counts(), [1, 2, 25, 202, 620, 900, 285, 13]);
+        assert_eq!(distribution.states(), 2048);
+        assert_eq!(distribution.diameter(), 7);
+
+        let corners = |cube: &Cube| (*cube.corner_permutation(), *cube.corner_orientation());
+        let distribution = Distribution::explore(&subgroup("<R,U>"), corners, 20);
+        assert_eq!(distribution.states(), 120 * 3usize.pow(5));
+    }
+
+    #[test]
+    fn matches_the_half_turn_group() {
+        let distribution = Distribution::explore(&subgroup("<U2,D2,F2,B2,R2,L2>"), Cube::clone, 20);
+        assert_eq!(
+            distribution.counts(),
+            [
+                1, 6, 27, 120, 519, 1932, 6484, 20310, 55034, 113892, 178495, 179196, 89728, 16176,
+                1488, 144
+            ]
+        );
+        assert_eq!(distribution.states(), 663552);
+        assert!((distribution.average() - 10.13).abs() < 0.01);
+    }
+}
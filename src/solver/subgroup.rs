@@ -0,0 +1,477 @@
+use std::{fmt::Display, str::FromStr};
+
+use crate::cube::{
+    Cube,
+    r#move::Move,
+    move_sequence::Sequence,
+    notation::{self, HOME},
+    simple_move::{Side, Turn},
+    slice_move::{Slice, SliceMove},
+};
+
+/// The moves a search may use, written like `<R,U>`, `<U,D,R2,L2,F2,B2>` or
+/// `<M,U>`.
+///
+/// A plain side or slice allows all three of its turns, a turn like `R2` or
+/// `U'` only allows itself, and may be repeated to make the others. Slice moves (`M`, `E`, `S`) and wide moves (`r`, `Rw`) turn
+/// the centers, so the search sees the pieces from the corners and only takes
+/// the centers being back in place as solved. The cube has to start with its
+/// centers in place too, as after face turns. Rotations aren't allowed.
+///
+/// States that these moves can't reach are partly recognised up front, by
+/// checking that every piece can be moved and twisted into its current slot
+/// and that the permutation parities are possible.
+#[derive(Debug, Clone)]
+pub struct Subgroup<T: Move> {
+    names: Vec<String>,
+    moves: Vec<T>,
+    generators: Vec<Generator<T>>,
+    corner_orbits: [usize; 24],
+    edge_orbits: [usize; 24],
+    odd_corners: bool,
+    odd_edges: bool,
+}
+
+/// One move of a subgroup as the search turns it.
+#[derive(Debug, Clone)]
+pub(crate) struct Generator<T: Move> {
+    /// The face turn, `None` for slice and wide moves.
+    pub(crate) face: Option<T>,
+    /// How a slice or wide move is written.
+    pub(crate) name: Option<String>,
+    /// The solved cube after the move, seen from the corners.
+    pub(crate) turned: Cube,
+    /// The face turns `notation::expand` writes the move as, and the frame it
+    /// leaves behind.
+    pub(crate) expanded: Vec<T>,
+    pub(crate) rotation: [Side; 6],
+    /// Moves of one layer, such as `M` and `M2`, share it. Layers on the same
+    /// axis commute.
+    pub(crate) layer: usize,
+    pub(crate) axis: u8,
+    /// The face turn a hand makes for the move, which is what regrip
+    /// penalties look at: the face itself, the side of a wide move, or the
+    /// side a slice turns like.
+    pub(crate) hand: T,
+    /// Whether any two moves of the layer in a row make another of its moves
+    /// or nothing, so a search never has to turn the layer twice in a row.
+    pub(crate) closed: bool,
+}
+
+impl<T: Move> Generator<T> {
+    fn face(face: T) -> Self {
+        Generator {
+            turned: Cube::create_solved().apply_move(&face),
+            expanded: vec![face.clone()],
+            rotation: HOME,
+            layer: face.side() as usize,
+            axis: face.side().axis(),
+            closed: false,
+            hand: face.clone(),
+            face: Some(face),
+            name: None,
+        }
+    }
+
+    /// A slice or wide move like `M'` or `r2`, `None` for anything else.
+    fn written(name: &str) -> Option<Self> {
+        let letter = name.chars().next()?;
+        let wide = letter.is_ascii_lowercase() || name.contains('w');
+        if notation::split(name).len() != 1 || "xyz".contains(letter) {
+            return None;
+        }
+        let mut rotation = HOME;
+        let expanded: Vec<T> = notation::expand_from(name, &mut rotation).ok()?;
+        if rotation == HOME {
+            return None;
+        }
+        // a slice is expanded as the side it turns against first, a wide move
+        // as the side opposite to it
+        let turns = expanded[0].turns();
+        let (turned, layer, axis, hand) = if wide {
+            let side = expanded[0].side().opposite();
+            let with_side = matches!(side, Side::LEFT | Side::DOWN | Side::FRONT);
+            let slice = SliceMove {
+                slice: slice_of(side.axis()),
+                turns: if with_side { turns } else { turns.invert() },
+            };
+            let face = Cube::create_solved().apply_move(&T::from_parts(side, turns));
+            (
+                slice.apply(&face),
+                9 + side as usize,
+                side.axis(),
+                T::from_parts(side, turns),
+            )
+        } else {
+            let axis = expanded[0].side().axis();
+            let slice = SliceMove {
+                slice: slice_of(axis),
+                turns,
+            };
+            (
+                slice.apply(&Cube::create_solved()),
+                6 + axis as usize,
+                axis,
+                T::from_parts(expanded[1].side(), turns),
+            )
+        };
+        Some(Generator {
+            face: None,
+            name: Some(name.to_string()),
+            turned,
+            expanded,
+            rotation,
+            layer,
+            axis,
+            hand,
+            closed: false,
+        })
+    }
+}
+
+/// The slice between the two sides of `axis`.
+fn slice_of(axis: u8) -> Slice {
+    match axis {
+        0 => Slice::E,
+        1 => Slice::S,
+        _ => Slice::M,
+    }
+}
+
+impl<T: Move> Subgroup<T> {
+    pub fn all() -> Self {
+        Self::from_moves(Vec::new(), T::all_moves().to_vec())
+    }
+
+    pub fn from_moves(names: Vec<String>, moves: Vec<T>) -> Self {
+        Self::from_generators(names, moves.into_iter().map(Generator::face).collect())
+    }
+
+    fn from_generators(names: Vec<String>, mut generators: Vec<Generator<T>>) -> Self {
+        let solved = Cube::create_solved();
+        for index in 0..generators.len() {
+            let layer: Vec<&Cube> = generators
+                .iter()
+                .filter(|generator| generator.layer == generators[index].layer)
+                .map(|generator| &generator.turned)
+                .collect();
+            generators[index].closed = layer.iter().all(|first| {
+                layer.iter().all(|second| {
+                    let both = first.compose(second);
+                    both == solved || layer.contains(&&both)
+                })
+            });
+        }
+        let mut corner_orbits: [usize; 24] = std::array::from_fn(|index| index);
+        let mut edge_orbits: [usize; 24] = std::array::from_fn(|index| index);
+        let mut odd_corners = false;
+        let mut odd_edges = false;
+
+        for generator in &generators {
+            let turned = &generator.turned;
+            for position in 0..8 {
+                let from = turned.corner_permutation()[position] as usize;
+                let twist = turned.corner_orientation()[position] as usize;
+                for orientation in 0..3 {
+                    union(
+                        &mut corner_orbits,
+                        from * 3 + orientation,
+                        position * 3 + (orientation + twist) % 3,
+                    );
+                }
+            }
+            for position in 0..12 {
+                let from = turned.edge_permutation()[position] as usize;
+                let flip = turned.edge_orientation()[position] as usize;
+                for orientation in 0..2 {
+                    union(
+                        &mut edge_orbits,
+                        from * 2 + orientation,
+                        position * 2 + (orientation + flip) % 2,
+                    );
+                }
+            }
+            odd_corners |= turned.corner_parity();
+            odd_edges |= turned.edge_parity();
+        }
+
+        Subgroup {
+            names,
+            moves: generators
+                .iter()
+                .filter_map(|generator| generator.face.clone())
+                .collect(),
+            generators,
+            corner_orbits,
+            edge_orbits,
+            odd_corners,
+            odd_edges,
+        }
+    }
+
+    /// The face turns among the moves.
+    pub fn moves(&self) -> &[T] {
+        &self.moves
+    }
+
+    /// The solved cube after each move, seen from the corners.
+    pub fn turned(&self) -> Vec<Cube> {
+        self.generators
+            .iter()
+            .map(|generator| generator.turned.clone())
+            .collect()
+    }
+
+    pub(crate) fn generators(&self) -> &[Generator<T>] {
+        &self.generators
+    }
+
+    /// The face turns of the moves at `path`, with the rotations of slice and
+    /// wide moves worked out the way `notation::expand` does.
+    pub(crate) fn sequence(&self, path: &[usize]) -> Sequence<T> {
+        let mut frame = HOME;
+        let mut moves = Vec::new();
+        for &index in path {
+            let generator = &self.generators[index];
+            moves.extend(
+                generator
+                    .expanded
+                    .iter()
+                    .map(|r#move| T::from_parts(frame[r#move.side() as usize], r#move.turns())),
+            );
+            frame = generator.rotation.map(|side| frame[side as usize]);
+        }
+        moves.into_iter().collect()
+    }
+
+    /// Whether no invariant rules out solving `cube` with these moves. This
+    /// is only necessary: a `false` is definite, but some cubes pass without
+    /// being solvable, like an A-perm in `<R,U>`, whose corners move freely
+    /// between the slots but not into every permutation.
+    pub fn might_solve(&self, cube: &Cube) -> bool {
+        if !cube.is_solvable() {
+            return false;
+        }
+        if cube.corner_parity() && !self.odd_corners || cube.edge_parity() && !self.odd_edges {
+            return false;
+        }
+        let mut corner_orbits = self.corner_orbits;
+        let corners_fit = (0..8).all(|position| {
+            let piece = cube.corner_permutation()[position] as usize;
+            let orientation = cube.corner_orientation()[position] as usize;
+            find(&mut corner_orbits, piece * 3)
+                == find(&mut corner_orbits, position * 3 + orientation)
+        });
+        let mut edge_orbits = self.edge_orbits;
+        let edges_fit = (0..12).all(|position| {
+            let piece = cube.edge_permutation()[position] as usize;
+            let orientation = cube.edge_orientation()[position] as usize;
+            find(&mut edge_orbits, piece * 2) == find(&mut edge_orbits, position * 2 + orientation)
+        });
+        corners_fit && edges_fit
+    }
+}
+
+impl<T: Move + Display> Subgroup<T> {
+    /// Every turn of `sides`, like `<R,U,L>`.
+    pub fn of_sides(sides: &[Side]) -> Self {
+        let names = sides
+            .iter()
+            .map(|&side| T::from_parts(side, Turn::RIGHT).to_string())
+            .collect();
+        let moves = T::all_moves()
+            .into_iter()
+            .filter(|r#move| sides.contains(&r#move.side()))
+            .collect();
+        Self::from_moves(names, moves)
+    }
+
+    /// The moves at `path` as they're written, slice and wide moves included.
+    pub(crate) fn written(&self, path: &[usize]) -> String {
+        path.iter()
+            .map(|&index| {
+                let generator = &self.generators[index];
+                match &generator.face {
+                    Some(face) => face.to_string(),
+                    None => generator.name.clone().unwrap_or_default(),
+                }
+            })
+            .collect::<Vec<_>>()
+            .join(" ")
+    }
+}
+
+impl<T: Move + FromStr> FromStr for Subgroup<T> {
+    type Err = <T as FromStr>::Err;
+
+    fn from_str(input: &str) -> Result<Self, Self::Err> {
+        let names: Vec<String> = input
+            .trim()
+            .trim_start_matches('<')
+            .trim_end_matches('>')
+            .split(',')
+            .map(|name| name.trim().to_string())
+            .filter(|name| !name.is_empty())
+            .collect();
+
+        let mut generators = Vec::new();
+        for name in &names {
+            let turns = if name.chars().count() == 1 {
+                ["", "'", "2"]
+                    .map(|turns| format!("{name}{turns}"))
+                    .to_vec()
+            } else {
+                vec![name.clone()]
+            };
+            for r#move in turns {
+                generators.push(match r#move.parse() {
+                    Ok(face) => Generator::face(face),
+                    Err(error) => Generator::written(&r#move).ok_or(error)?,
+                });
+            }
+        }
+        Ok(Self::from_generators(names, generators))
+    }
+}
+
+impl<T: Move> Display for Subgroup<T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if self.names.is_empty() {
+            return write!(f, "<U,D,F,B,R,L>");
+        }
+        write!(f, "<{}>", self.names.join(","))
+    }
+}
+
+fn find(parents: &mut [usize], index: usize) -> usize {
+    let mut root = index;
+    while parents[root] != root {
+        root = parents[root];
+    }
+    parents[index] = root;
+    root
+}
+
+fn union(parents: &mut [usize], a: usize, b: usize) {
+    let a = find(parents, a);
+    let b = find(parents, b);
+    parents[a] = b;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        cube::{mask::Mask, metric::Metric, scrambled, simple_move::SimpleMove},
+        solver::Search,
+    };
+
+    #[test]
+    fn parses_generators() {
+        let subgroup: Subgroup<SimpleMove> = "<R, U>".parse().unwrap();
+        assert_eq!(subgroup.moves().len(), 6);
+        assert_eq!(subgroup.to_string(), "<R,U>");
+
+        let subgroup: Subgroup<SimpleMove> = "<U,D,R2,L2,F2,B2>".parse().unwrap();
+        assert_eq!(subgroup.moves().len(), 10);
+
+        assert!("<R,X>".parse::<Subgroup<SimpleMove>>().is_err());
+        assert!("<R,x>".parse::<Subgroup<SimpleMove>>().is_err());
+
+        let subgroup: Subgroup<SimpleMove> = "<M,U,r2,Rw'>".parse().unwrap();
+        assert_eq!(subgroup.moves().len(), 3);
+        assert_eq!(subgroup.turned().len(), 8);
+        assert_eq!(subgroup.to_string(), "<M,U,r2,Rw'>");
+
+        let subgroup: Subgroup<SimpleMove> = Subgroup::of_sides(&[Side::RIGHT, Side::UP]);
+        assert_eq!(subgroup.moves().len(), 6);
+        assert_eq!(subgroup.to_string(), "<R,U>");
+    }
+
+    #[test]
+    fn detects_unreachable_states() {
+        let two_gen: Subgroup<SimpleMove> = "<R,U>".parse().unwrap();
+        assert!(two_gen.might_solve(&scrambled("R U R' U R U2 R'")));
+        assert!(!two_gen.might_solve(&scrambled("F")));
+        assert!(!two_gen.might_solve(&scrambled("L")));
+        // only a necessary condition: `<R,U>` can't solve an A-perm
+        assert!(two_gen.might_solve(&scrambled("R' F R' B2 R F' R' B2 R2")));
+
+        let slices: Subgroup<SimpleMove> = "<M,U>".parse().unwrap();
+        assert!(slices.might_solve(&scrambled("M' U2 M U2")));
+        assert!(!slices.might_solve(&scrambled("R")));
+
+        let domino: Subgroup<SimpleMove> = "<U,D,R2,L2,F2,B2>".parse().unwrap();
+        assert!(domino.might_solve(&scrambled("R2 U F2 D'")));
+        assert!(!domino.might_solve(&scrambled("R")));
+
+        let half_turns: Subgroup<SimpleMove> = "<U2,D2,R2,L2,F2,B2>".parse().unwrap();
+        assert!(!half_turns.might_solve(&scrambled("U")));
+    }
+
+    #[test]
+    fn solves_within_subgroup() {
+        let two_gen: Subgroup<SimpleMove> = "<R,U>".parse().unwrap();
+        let cube = scrambled("R U2 R' U' R U' R'");
+        let solution = Search::new()
+            .subgroup(two_gen.clone())
+            .solve(cube.clone())
+            .unwrap();
+        assert_eq!(cube.apply(&solution), Cube::create_solved());
+        assert!(solution.into_iter().all(|m| two_gen.moves().contains(&m)));
+
+        assert!(
+            Search::new()
+                .subgroup(two_gen)
+                .solve(scrambled("F"))
+                .is_none()
+        );
+
+        // without their inverses, turns are repeated instead
+        for (generators, scramble, solution) in [
+            ("<R2,U'>", "U", "U'"),
+            ("<R2,U'>", "U'", "U' U' U'"),
+            ("<U,R'>", "R", "R'"),
+            ("<U,R'>", "R2", "R' R'"),
+            ("<U,Rw'>", "Rw U Rw'", "Rw' Rw' Rw' U' Rw'"),
+            ("<U,Rw'>", "Rw' U Rw", "Rw' U' Rw' Rw' Rw'"),
+        ] {
+            let search = Search::<SimpleMove>::new().subgroup(generators.parse().unwrap());
+            let cube = scrambled(scramble);
+            let (written, moves) = search
+                .solve_written(cube.clone())
+                .unwrap_or_else(|| panic!("{generators} {scramble}"));
+            assert_eq!(written, solution);
+            assert_eq!(cube.apply(&moves), Cube::create_solved());
+            let cross = search.goal(Mask::cross()).solve(cube).unwrap();
+            assert!(cross.len() <= moves.len(), "{cross}");
+        }
+    }
+
+    #[test]
+    fn solves_with_slice_and_wide_moves() {
+        let cube = scrambled("M' U M U2 M' U M");
+        let search = Search::<SimpleMove>::new()
+            .subgroup("<M,U>".parse().unwrap())
+            .metric(Metric::STM);
+        let (written, moves) = search.solve_written(cube.clone()).unwrap();
+        assert_eq!(Metric::STM.count(&written), 7);
+        assert_eq!(cube.apply(&moves), Cube::create_solved());
+        assert_eq!(moves, notation::expand(&written).unwrap());
+
+        let cube = scrambled("r U R' U' r' F R F'");
+        let (written, moves) = Search::<SimpleMove>::new()
+            .subgroup("<r,R,U,F>".parse().unwrap())
+            .solve_written(cube.clone())
+            .unwrap();
+        assert_eq!(cube.apply(&moves), Cube::create_solved());
+        assert!(notation::split(&written).len() <= 8, "{written}");
+
+        // the centers have to end up in place as well, not just the pieces
+        // around them
+        let cube = scrambled("U M2 U' M2");
+        let (written, moves) = search.solve_written(cube.clone()).unwrap();
+        assert_eq!(written, "M2 U M2 U'");
+        assert_eq!(cube.apply(&moves), Cube::create_solved());
+    }
+}
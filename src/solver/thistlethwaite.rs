@@ -0,0 +1,329 @@
+use std::{
+    collections::{HashMap, HashSet},
+    sync::LazyLock,
+};
+
+use crate::{
+    cube::{Cube, r#move::Move, move_sequence::Sequence, simple_move::ALL_MOVES},
+    table::{Table, TableCache},
+};
+
+const VERSION: u32 = 1;
+
+/// The moves of each phase, as indices into `Move::all_moves`.
+const PHASE_MOVES: [&[usize]; 4] = [
+    // <U, D, F, B, R, L>
+    &[0, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15, 16, 17],
+    // <U, D, F2, B2, R, L>
+    &[0, 1, 2, 3, 4, 5, 8, 11, 12, 13, 14, 15, 16, 17],
+    // <U, D, F2, B2, R2, L2>
+    &[0, 1, 2, 3, 4, 5, 8, 11, 14, 17],
+    // <U2, D2, F2, B2, R2, L2>
+    &[2, 5, 8, 11, 14, 17],
+];
+
+const E_SLICE: [u8; 4] = [4, 5, 6, 7];
+const M_SLICE: [u8; 4] = [0, 2, 8, 10];
+
+static PHASES: LazyLock<[Phase; 4]> = LazyLock::new(|| {
+    let half_turn_corners: HashSet<u64> = Component::new(PHASE_MOVES[3], corners)
+        .keys
+        .into_iter()
+        .collect();
+    [
+        Phase::new(
+            "thistlethwaite-1",
+            PHASE_MOVES[0],
+            &[edge_orientation],
+            |keys| keys[0] == 0,
+        ),
+        Phase::new(
+            "thistlethwaite-2",
+            PHASE_MOVES[1],
+            &[corner_orientation, e_slice],
+            |keys| keys[0] == 0 && keys[1] == slice(&Cube::create_solved(), E_SLICE),
+        ),
+        Phase::new(
+            "thistlethwaite-3",
+            PHASE_MOVES[2],
+            &[corners, m_slice],
+            |keys| {
+                half_turn_corners.contains(&keys[0])
+                    && keys[1] == slice(&Cube::create_solved(), M_SLICE)
+            },
+        ),
+        Phase::new(
+            "thistlethwaite-4",
+            PHASE_MOVES[3],
+            &[corners, edges],
+            |keys| {
+                keys[0] == corners(&Cube::create_solved())
+                    && keys[1] == edges(&Cube::create_solved())
+            },
+        ),
+    ]
+});
+
+/// Solves `cube` with Thistlethwaite's algorithm, one sequence per phase.
+///
+/// Each phase brings the cube into a smaller group, from which the rest can be
+/// solved with fewer kinds of moves: first every edge is oriented, then every
+/// corner and the `E` slice edges, then the pieces are put where half turns
+/// can solve them, and finally half turns solve the cube. Every phase has an
+/// exact table of distances to its goal, so each phase is as short as it can
+/// be, at most 7, 10, 13 and 15 moves. The tables are built the first time
+/// they're needed and stored in `TableCache::from_env` for later runs.
+pub fn solve<T: Move>(cube: &Cube) -> Option<[Sequence<T>; 4]> {
+    if !cube.is_solvable() {
+        return None;
+    }
+    let mut cube = cube.clone();
+    let mut phases: [Sequence<T>; 4] = Default::default();
+    for (phase, moves) in PHASES.iter().zip(&mut phases) {
+        let mut index = phase.index(&cube)?;
+        while phase.distances[index] > 0 {
+            let (next, r#move) = phase
+                .moves
+                .iter()
+                .enumerate()
+                .map(|(position, &r#move)| (phase.next(index, position), r#move))
+                .find(|(next, _)| phase.distances[*next] < phase.distances[index])?;
+            let r#move = T::all_moves()[r#move].clone();
+            cube = cube.apply_move(&r#move);
+            *moves = moves.apply(&r#move);
+            index = next;
+        }
+    }
+    Some(phases)
+}
+
+/// The distance to a phase's goal for every combination of its components.
+pub(crate) struct Phase {
+    pub(crate) moves: &'static [usize],
+    components: Vec<Component>,
+    pub(crate) distances: Table,
+}
+
+impl Phase {
+    /// The distances are stored in `TableCache::from_env` as `name`, so they
+    /// are only searched for the first time the phase is needed.
+    pub(crate) fn new(
+        name: &str,
+        moves: &'static [usize],
+        encoders: &[fn(&Cube) -> u64],
+        goal: impl Fn(&[u64]) -> bool,
+    ) -> Self {
+        let components: Vec<Component> = encoders
+            .iter()
+            .map(|&encode| Component::new(moves, encode))
+            .collect();
+        let mut phase = Phase {
+            moves,
+            components,
+            distances: Table::Generated(Vec::new()),
+        };
+        let distances = TableCache::from_env().load(name, VERSION, || phase.search(goal));
+        phase.distances = distances;
+        phase
+    }
+
+    /// The distances to `goal`, by a breadth-first search from every state
+    /// meeting it.
+    fn search(&self, goal: impl Fn(&[u64]) -> bool) -> Vec<u8> {
+        let size = self
+            .components
+            .iter()
+            .map(|component| component.keys.len())
+            .product();
+        let mut distances = vec![u8::MAX; size];
+        let mut frontier: Vec<usize> = (0..size).filter(|&index| goal(&self.keys(index))).collect();
+        for &index in &frontier {
+            distances[index] = 0;
+        }
+        let mut distance = 0;
+        while !frontier.is_empty() {
+            distance += 1;
+            let mut next = Vec::new();
+            for index in frontier {
+                for position in 0..self.moves.len() {
+                    let moved = self.next(index, position);
+                    if distances[moved] == u8::MAX {
+                        distances[moved] = distance;
+                        next.push(moved);
+                    }
+                }
+            }
+            frontier = next;
+        }
+        distances
+    }
+
+    pub(crate) fn index(&self, cube: &Cube) -> Option<usize> {
+        self.components.iter().try_fold(0, |index, component| {
+            let value = component.indices.get(&(component.encode)(cube))?;
+            Some(index * component.keys.len() + *value as usize)
+        })
+    }
+
+    fn keys(&self, mut index: usize) -> Vec<u64> {
+        let mut keys: Vec<u64> = self
+            .components
+            .iter()
+            .rev()
+            .map(|component| {
+                let key = component.keys[index % component.keys.len()];
+                index /= component.keys.len();
+                key
+            })
+            .collect();
+        keys.reverse();
+        keys
+    }
+
+    /// The index after the phase's move at `position`.
+    pub(crate) fn next(&self, mut index: usize, position: usize) -> usize {
+        let mut next = 0;
+        let mut scale = 1;
+        for component in self.components.iter().rev() {
+            let size = component.keys.len();
+            next += component.transitions[index % size][position] as usize * scale;
+            index /= size;
+            scale *= size;
+        }
+        next
+    }
+}
+
+/// One part of a phase's state, like the orientation of the corners, with
+/// every value the phase's moves can reach from the solved cube numbered.
+struct Component {
+    encode: fn(&Cube) -> u64,
+    keys: Vec<u64>,
+    indices: HashMap<u64, u32>,
+    /// The index each move leads to, per index.
+    transitions: Vec<Vec<u32>>,
+}
+
+impl Component {
+    fn new(moves: &[usize], encode: fn(&Cube) -> u64) -> Self {
+        let turned: Vec<Cube> = moves
+            .iter()
+            .map(|&r#move| Cube::create_solved().apply_move(&ALL_MOVES[r#move]))
+            .collect();
+        let solved = Cube::create_solved();
+        let mut keys = vec![encode(&solved)];
+        let mut indices = HashMap::from([(keys[0], 0)]);
+        let mut cubes = vec![solved];
+        let mut transitions = Vec::new();
+        let mut index = 0;
+        // Any cube with the same key moves to the same keys, so the first one
+        // found stands in for the others.
+        while index < cubes.len() {
+            let row = turned
+                .iter()
+                .map(|turned| {
+                    let moved = cubes[index].compose(turned);
+                    let key = encode(&moved);
+                    *indices.entry(key).or_insert_with(|| {
+                        keys.push(key);
+                        cubes.push(moved);
+                        keys.len() as u32 - 1
+                    })
+                })
+                .collect();
+            transitions.push(row);
+            index += 1;
+        }
+        Component {
+            encode,
+            keys,
+            indices,
+            transitions,
+        }
+    }
+}
+
+fn edge_orientation(cube: &Cube) -> u64 {
+    pack(cube.edge_orientation(), 1)
+}
+
+fn corner_orientation(cube: &Cube) -> u64 {
+    pack(cube.corner_orientation(), 2)
+}
+
+fn corners(cube: &Cube) -> u64 {
+    pack(cube.corner_permutation(), 3)
+}
+
+fn edges(cube: &Cube) -> u64 {
+    pack(cube.edge_permutation(), 4)
+}
+
+fn e_slice(cube: &Cube) -> u64 {
+    slice(cube, E_SLICE)
+}
+
+fn m_slice(cube: &Cube) -> u64 {
+    slice(cube, M_SLICE)
+}
+
+/// The positions holding the edges of a slice, as bits.
+fn slice(cube: &Cube, edges: [u8; 4]) -> u64 {
+    let positions: Vec<u8> = cube
+        .edge_permutation()
+        .iter()
+        .map(|edge| edges.contains(edge) as u8)
+        .collect();
+    pack(&positions, 1)
+}
+
+fn pack(values: &[u8], bits: u32) -> u64 {
+    values
+        .iter()
+        .fold(0, |packed, &value| packed << bits | value as u64)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cube::{mask::Mask, simple_move::SimpleMove};
+
+    #[test]
+    fn solves_in_four_phases() {
+        let scramble: Sequence<SimpleMove> = "D2 F' R2 U L2 B' R D' F2 L U2 B R' F D2 R2 B2 U"
+            .parse()
+            .unwrap();
+        let mut cube = Cube::create_solved().apply(&scramble);
+        let phases = solve::<SimpleMove>(&cube).unwrap();
+
+        for ((phase, allowed), longest) in phases.iter().zip(PHASE_MOVES).zip([7, 10, 13, 15]) {
+            assert!(phase.len() <= longest, "{phase}");
+            for r#move in phase.moves() {
+                assert!(
+                    allowed.iter().any(|&index| ALL_MOVES[index] == *r#move),
+                    "{phase}"
+                );
+            }
+            cube = cube.apply(phase);
+        }
+        assert_eq!(cube, Cube::create_solved());
+
+        let oriented = Cube::create_solved().apply(&scramble).apply(&phases[0]);
+        assert!(Mask::edge_orientation().matches(&oriented));
+    }
+
+    #[test]
+    fn solved_cube_needs_no_moves() {
+        let phases = solve::<SimpleMove>(&Cube::create_solved()).unwrap();
+        assert!(phases.iter().all(Sequence::is_empty));
+    }
+
+    #[test]
+    fn stores_the_phase_tables() {
+        assert!(PHASES.iter().all(|phase| phase.distances.is_mapped()));
+        let cache = TableCache::from_env();
+        for phase in 1..=4 {
+            assert!(cache.path(&format!("thistlethwaite-{phase}")).exists());
+        }
+    }
+}
@@ -0,0 +1,240 @@
+use std::{
+    fs::{self, File},
+    io::{self, Write},
+    ops::Deref,
+    path::{Path, PathBuf},
+    sync::atomic::{AtomicUsize, Ordering},
+};
+
+use memmap2::Mmap;
+
+const MAGIC: &[u8; 4] = b"RCST";
+const FORMAT_VERSION: u32 = 1;
+// magic, format version, table version, data length, checksum
+const HEADER_LEN: usize = 4 + 4 + 4 + 8 + 8;
+
+/// Tells apart the temporary files of tables stored at the same time.
+static WRITES: AtomicUsize = AtomicUsize::new(0);
+
+/// Directory of generated lookup tables, so expensive tables are only built
+/// the first time they are needed.
+///
+/// Each table is stored as `<name>.table`: a header holding the format and
+/// table versions, the data length and a checksum, followed by the raw bytes.
+/// Files that fail any of these checks are rebuilt and rewritten.
+#[derive(Debug, Clone)]
+pub struct TableCache {
+    directory: PathBuf,
+}
+
+#[derive(Debug)]
+pub enum Table {
+    Mapped(Mmap),
+    Generated(Vec<u8>),
+}
+
+impl TableCache {
+    pub fn new(directory: impl Into<PathBuf>) -> Self {
+        TableCache {
+            directory: directory.into(),
+        }
+    }
+
+    /// Uses `RUSTCUBESOLVER_TABLES` when set, otherwise `./tables`.
+    pub fn from_env() -> Self {
+        match std::env::var_os("RUSTCUBESOLVER_TABLES") {
+            Some(directory) => TableCache::new(directory),
+            None => TableCache::new("tables"),
+        }
+    }
+
+    pub fn path(&self, name: &str) -> PathBuf {
+        self.directory.join(format!("{name}.table"))
+    }
+
+    /// Loads the table `name` at `version`, calling `generate` and storing the
+    /// result when there is no valid file for it yet.
+    ///
+    /// When the file can't be written, the generated table is returned as is
+    /// and `Table::is_mapped` is false.
+    pub fn load(&self, name: &str, version: u32, generate: impl FnOnce() -> Vec<u8>) -> Table {
+        let path = self.path(name);
+        if let Some(table) = Self::map(&path, version) {
+            return table;
+        }
+
+        let data = generate();
+        if Self::store(&path, version, &data).is_err() {
+            return Table::Generated(data);
+        }
+        Self::map(&path, version).unwrap_or(Table::Generated(data))
+    }
+
+    fn map(path: &Path, version: u32) -> Option<Table> {
+        let file = File::open(path).ok()?;
+        // SAFETY: table files are only ever replaced through a rename in
+        // `store`, never modified in place, so the mapping stays valid.
+        let mmap = unsafe { Mmap::map(&file) }.ok()?;
+        validate(&mmap, version)?;
+        Some(Table::Mapped(mmap))
+    }
+
+    /// Writes to a temporary file first and renames it into place, so a
+    /// table is never seen half written.
+    fn store(path: &Path, version: u32, data: &[u8]) -> io::Result<()> {
+        if let Some(directory) = path.parent() {
+            fs::create_dir_all(directory)?;
+        }
+        let temporary = path.with_extension(format!(
+            "table.{}.{}",
+            std::process::id(),
+            WRITES.fetch_add(1, Ordering::Relaxed)
+        ));
+        let result =
+            Self::write(&temporary, version, data).and_then(|()| fs::rename(&temporary, path));
+        if result.is_err() {
+            let _ = fs::remove_file(&temporary);
+        }
+        result
+    }
+
+    fn write(path: &Path, version: u32, data: &[u8]) -> io::Result<()> {
+        let mut file = File::create(path)?;
+        file.write_all(MAGIC)?;
+        file.write_all(&FORMAT_VERSION.to_le_bytes())?;
+        file.write_all(&version.to_le_bytes())?;
+        file.write_all(&(data.len() as u64).to_le_bytes())?;
+        file.write_all(&checksum(data).to_le_bytes())?;
+        file.write_all(data)?;
+        file.sync_all()
+    }
+}
+
+impl Table {
+    pub fn data(&self) -> &[u8] {
+        match self {
+            Table::Mapped(mmap) => &mmap[HEADER_LEN..],
+            Table::Generated(data) => data,
+        }
+    }
+
+    pub fn is_mapped(&self) -> bool {
+        matches!(self, Table::Mapped(_))
+    }
+}
+
+impl Deref for Table {
+    type Target = [u8];
+
+    fn deref(&self) -> &[u8] {
+        self.data()
+    }
+}
+
+fn validate(file: &[u8], version: u32) -> Option<()> {
+    let header = file.get(..HEADER_LEN)?;
+    let read_u32 = |at: usize| u32::from_le_bytes(header[at..at + 4].try_into().unwrap());
+    let read_u64 = |at: usize| u64::from_le_bytes(header[at..at + 8].try_into().unwrap());
+
+    let valid = &header[..4] == MAGIC
+        && read_u32(4) == FORMAT_VERSION
+        && read_u32(8) == version
+        && read_u64(12) == (file.len() - HEADER_LEN) as u64
+        && read_u64(20) == checksum(&file[HEADER_LEN..]);
+    valid.then_some(())
+}
+
+/// 64-bit FNV-1a.
+pub(crate) fn checksum(data: &[u8]) -> u64 {
+    data.iter().fold(0xcbf29ce484222325, |hash, byte| {
+        (hash ^ *byte as u64).wrapping_mul(0x100000001b3)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A cache in a fresh temporary directory, removed again on drop.
+    struct TemporaryCache(TableCache);
+
+    impl Deref for TemporaryCache {
+        type Target = TableCache;
+
+        fn deref(&self) -> &TableCache {
+            &self.0
+        }
+    }
+
+    impl Drop for TemporaryCache {
+        fn drop(&mut self) {
+            let _ = fs::remove_dir_all(&self.0.directory);
+        }
+    }
+
+    fn cache(test: &str) -> TemporaryCache {
+        let directory =
+            std::env::temp_dir().join(format!("rustcubesolver-{test}-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&directory);
+        TemporaryCache(TableCache::new(directory))
+    }
+
+    /// Swaps a file in the way `store` does, since an earlier table may
+    /// still map the old one.
+    fn replace(path: &Path, bytes: &[u8]) {
+        let temporary = path.with_extension("corrupt");
+        fs::write(&temporary, bytes).unwrap();
+        fs::rename(&temporary, path).unwrap();
+    }
+
+    #[test]
+    fn generates_once_then_maps() {
+        let cache = cache("generates-once");
+        let table = cache.load("numbers", 1, || (0..=255).collect());
+        assert!(table.is_mapped());
+        assert_eq!(table.len(), 256);
+
+        let table = cache.load("numbers", 1, || panic!("table should be cached"));
+        assert!(table.is_mapped());
+        assert_eq!(table[200], 200);
+    }
+
+    #[test]
+    fn regenerates_stale_version() {
+        let cache = cache("stale");
+        cache.load("numbers", 1, || vec![1; 10]);
+        let table = cache.load("numbers", 2, || vec![2; 10]);
+        assert_eq!(&*table, &[2; 10]);
+    }
+
+    #[test]
+    fn regenerates_corrupt_file() {
+        let cache = cache("corrupt");
+        cache.load("numbers", 1, || vec![1; 10]);
+
+        let path = cache.path("numbers");
+        let mut bytes = fs::read(&path).unwrap();
+        *bytes.last_mut().unwrap() ^= 0xff;
+        replace(&path, &bytes);
+
+        let table = cache.load("numbers", 1, || vec![3; 10]);
+        assert_eq!(&*table, &[3; 10]);
+
+        replace(&path, b"RCST");
+        let table = cache.load("numbers", 1, || vec![4; 10]);
+        assert_eq!(&*table, &[4; 10]);
+    }
+
+    #[test]
+    fn cleans_up_after_a_failed_write() {
+        let cache = cache("failed-write");
+        // A directory in the way makes the final rename fail.
+        fs::create_dir_all(cache.path("numbers").join("in-the-way")).unwrap();
+        let table = cache.load("numbers", 1, || vec![5; 10]);
+        assert!(!table.is_mapped());
+        assert_eq!(&*table, &[5; 10]);
+
+        let entries: Vec<_> = fs::read_dir(&cache.directory).unwrap().collect();
+        assert_eq!(entries.len(), 1);
+    }
+}